@@ -0,0 +1,37 @@
+//! polls a pending timer in a tight loop, as an eager executor would.
+//! run with `cargo run --release --example poll_bench`.
+
+use async_timers::{Clock, MockClock, TimeWheel};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::time::{Duration, Instant};
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+const POLLS: u32 = 1_000_000;
+
+fn bench<C: Clock>(name: &str, mut wheel: TimeWheel<(), C>) {
+    let waker = Waker::from(Arc::new(Noop));
+    let id = wheel.init_timer(Duration::from_secs(60), &waker).unwrap();
+
+    let start = Instant::now();
+    for _ in 0..POLLS {
+        assert!(black_box(wheel.poll(black_box(id), &waker)).is_pending());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name}: {POLLS} polls in {elapsed:?}, {:?} per poll",
+        elapsed / POLLS
+    );
+}
+
+fn main() {
+    // the std clock read dominates, the mock clock shows the cost of the wheel itself
+    bench("std clock", TimeWheel::new());
+    bench("mock clock", TimeWheel::new_with_clock(MockClock::new()));
+}
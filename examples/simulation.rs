@@ -0,0 +1,32 @@
+//! drives a wheel from a logical tick counter, as a discrete event simulator would.
+
+use async_timers::{TickClock, TimeWheel};
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+
+struct Event(&'static str);
+
+impl Wake for Event {
+    fn wake(self: Arc<Self>) {
+        println!("{}", self.0);
+    }
+}
+
+fn main() {
+    let clock = TickClock::<10>::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+
+    for (ticks, name) in [(3, "arrival"), (25, "departure"), (7, "service")] {
+        wheel
+            .init_timer_ticks(ticks, &Waker::from(Arc::new(Event(name))))
+            .unwrap();
+    }
+
+    // jump straight to the tick of each event instead of stepping one tick at a time
+    while let Some(next) = wheel.next_deadline() {
+        clock.advance_ticks(next.as_millis() as u64 / 10);
+        if wheel.tick() > 0 {
+            println!("  at tick {}", clock.ticks());
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{Clock, DefaultClock, DropOutcome, TimeWheel, TimerError, TimerId};
+use core::{
+    cell::{RefCell, RefMut},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// wheel usable from a shared reference on a single thread, typically owned by futures whose
+/// `poll` only gets `&self`.
+///
+/// every method borrows the wheel for its duration only and wakers are called once the borrow
+/// is released, so they may use the wheel again. the wheel is not `Sync`, it is meant for
+/// single threaded executors. only a waker using the wheel when it is cloned or dropped can
+/// borrow it twice, which panics.
+pub struct CellTimeWheel<C: Clock = DefaultClock> {
+    inner: RefCell<TimeWheel<(), C>>,
+}
+
+impl CellTimeWheel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_with_clock(DefaultClock::default())
+    }
+}
+
+impl Default for CellTimeWheel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> CellTimeWheel<C> {
+    /// creates a wheel reading the time from `clock`.
+    #[must_use]
+    pub fn new_with_clock(clock: C) -> Self {
+        Self {
+            inner: RefCell::new(TimeWheel::new_with_clock(clock)),
+        }
+    }
+
+    pub fn arm(&self, duration: Duration, waker: &Waker) -> Result<TimerId, TimerError> {
+        self.wheel().init_timer(duration, waker)
+    }
+
+    pub fn cancel(&self, id: TimerId) -> DropOutcome {
+        self.wheel().drop(id)
+    }
+
+    pub fn poll(&self, id: TimerId, waker: &Waker) -> Poll<()> {
+        self.wheel().poll(id, waker)
+    }
+
+    /// fires the timers due since the last tick, returns how many were woken.
+    pub fn tick(&self) -> usize {
+        let wakeups = {
+            let mut wheel = self.wheel();
+            let now = wheel.clock.now();
+            wheel.advance_deferred(now)
+        };
+        let fired = wakeups.len();
+        for wakeup in wakeups {
+            wakeup.wake();
+        }
+        fired
+    }
+
+    fn wheel(&self) -> RefMut<'_, TimeWheel<(), C>> {
+        self.inner.try_borrow_mut().expect(
+            "CellTimeWheel used reentrantly, from a waker or callback run during one of its calls",
+        )
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::rc::Rc;
+use core::{cell::Cell, time::Duration};
+
+/// time source driving a [`TimeWheel`](crate::TimeWheel).
+///
+/// the clock is expected to be monotonic, a reading older than the last tick is treated as no
+/// time elapsed and never rewinds the wheel.
+pub trait Clock {
+    type Instant: Copy + Ord;
+
+    fn now(&self) -> Self::Instant;
+
+    /// time elapsed from `earlier` to `later`, zero if `later` is before `earlier`.
+    fn duration_between(earlier: Self::Instant, later: Self::Instant) -> Duration;
+
+    /// instant `duration` after `instant`.
+    fn add(instant: Self::Instant, duration: Duration) -> Self::Instant;
+
+    /// blocks until `duration` elapsed on this clock, used by
+    /// [`TimeWheel::run_until_idle`](crate::TimeWheel::run_until_idle).
+    fn sleep(&self, duration: Duration);
+}
+
+/// clock reading [`std::time::Instant::now`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    type Instant = std::time::Instant;
+
+    #[inline]
+    fn now(&self) -> Self::Instant {
+        std::time::Instant::now()
+    }
+
+    #[inline]
+    fn duration_between(earlier: Self::Instant, later: Self::Instant) -> Duration {
+        later.saturating_duration_since(earlier)
+    }
+
+    #[inline]
+    fn add(instant: Self::Instant, duration: Duration) -> Self::Instant {
+        instant + duration
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// clock only moving when advanced by hand, its instants count milliseconds since its creation.
+/// clones share the same time, so a clone kept outside of the wheel drives it.
+///
+/// it is meant for tests and simulations, and for targets without `std` where the embedder
+/// advances it from its own time source.
+#[derive(Debug, Default, Clone)]
+pub struct MockClock {
+    now_ms: Rc<Cell<u64>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now_ms.set(Self::add(self.now_ms.get(), duration));
+    }
+}
+
+impl Clock for MockClock {
+    type Instant = u64;
+
+    #[inline]
+    fn now(&self) -> u64 {
+        self.now_ms.get()
+    }
+
+    #[inline]
+    fn duration_between(earlier: u64, later: u64) -> Duration {
+        Duration::from_millis(later.saturating_sub(earlier))
+    }
+
+    #[inline]
+    fn add(instant: u64, duration: Duration) -> u64 {
+        // saturates at the end of time instead of wrapping
+        let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        instant.saturating_add(ms)
+    }
+
+    /// advances the clock instead of waiting.
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+/// clock counting logical ticks instead of time, for discrete event simulations with no wall
+/// clock involved. each tick stands for `TICK_MS` milliseconds, the default matches the tick of
+/// [`TimeWheel::new`](crate::TimeWheel::new) so one tick of the clock is one tick of the wheel.
+/// clones share the same counter.
+#[derive(Debug, Default, Clone)]
+pub struct TickClock<const TICK_MS: u64 = 10> {
+    ticks: Rc<Cell<u64>>,
+}
+
+impl<const TICK_MS: u64> TickClock<TICK_MS> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// current tick count.
+    pub fn ticks(&self) -> u64 {
+        self.ticks.get()
+    }
+
+    pub fn advance_ticks(&self, ticks: u64) {
+        self.ticks.set(self.ticks.get().saturating_add(ticks));
+    }
+}
+
+impl<const TICK_MS: u64> Clock for TickClock<TICK_MS> {
+    type Instant = u64;
+
+    #[inline]
+    fn now(&self) -> u64 {
+        self.ticks.get()
+    }
+
+    #[inline]
+    fn duration_between(earlier: u64, later: u64) -> Duration {
+        Duration::from_millis(later.saturating_sub(earlier).saturating_mul(TICK_MS))
+    }
+
+    #[inline]
+    fn add(instant: u64, duration: Duration) -> u64 {
+        // rounded up, an instant is never reached before the duration elapsed
+        let ticks = duration.as_millis().div_ceil(TICK_MS as u128);
+        instant.saturating_add(u64::try_from(ticks).unwrap_or(u64::MAX))
+    }
+
+    /// advances the clock instead of waiting.
+    fn sleep(&self, duration: Duration) {
+        self.ticks.set(Self::add(self.ticks.get(), duration));
+    }
+}
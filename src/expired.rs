@@ -0,0 +1,49 @@
+use crate::{Clock, TimeWheel, TimerId, TimerStorage, TimerStore};
+
+/// iterator over the timers firing as the wheel advances, returned by [`TimeWheel::tick_expired`].
+///
+/// the wheel advances lazily, each call to `next` fires the following due timer only. timers
+/// left when the iterator is dropped stay due and fire on the next tick.
+pub struct Expired<'a, T, C: Clock, const N: usize, S: TimerStore<T> = TimerStorage<T>> {
+    wheel: &'a mut TimeWheel<T, C, N, S>,
+    now: C::Instant,
+    // fires left under the wheel's cap
+    budget: usize,
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> TimeWheel<T, C, N, S> {
+    /// advances the wheel to the current time while the returned iterator is consumed, yielding
+    /// each fired timer with its payload. interval timers keep their payload and yield None.
+    /// wakers and callbacks are still notified, the cap set with
+    /// [`TimeWheel::with_max_fires_per_tick`] bounds the number of items.
+    pub fn tick_expired(&mut self) -> Expired<'_, T, C, N, S> {
+        let now = self.clock.now();
+        let budget = self.max_fires_per_tick.unwrap_or(usize::MAX);
+        // set again by the first item, as advance_with does for a whole tick
+        self.fired_last_tick = false;
+        Expired {
+            wheel: self,
+            now,
+            budget,
+        }
+    }
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> Iterator for Expired<'_, T, C, N, S> {
+    type Item = (TimerId, Option<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.budget == 0 {
+            return None;
+        }
+        let mut fired = None;
+        self.wheel.advance_capped(self.now, 1, |id, wakeup| {
+            wakeup.wake();
+            fired = Some(id);
+        });
+        let id = fired?;
+        self.wheel.fired_last_tick = true;
+        self.budget -= 1;
+        Some((id, self.wheel.take_payload(id)))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Clock, DefaultClock, TimeWheel, TimerError, TimerId};
+use alloc::rc::Rc;
+use core::{
+    cell::RefCell,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use futures_core::Stream;
+
+/// stream yielding every time its interval timer fires, it never ends unless stopped.
+/// the timer is cancelled when the stream is stopped or dropped.
+pub struct IntervalStream<C: Clock = DefaultClock> {
+    wheel: Rc<RefCell<TimeWheel<(), C>>>,
+    // None once stopped
+    id: Option<TimerId>,
+}
+
+impl<C: Clock> TimeWheel<(), C> {
+    /// arms an interval timer on the shared wheel and returns a stream yielding on each fire.
+    pub fn interval_stream(
+        wheel: &Rc<RefCell<Self>>,
+        period: Duration,
+    ) -> Result<IntervalStream<C>, TimerError> {
+        // the real waker is registered on the first poll
+        let id = wheel.borrow_mut().init_interval(period, Waker::noop())?;
+        Ok(IntervalStream {
+            wheel: wheel.clone(),
+            id: Some(id),
+        })
+    }
+}
+
+impl<C: Clock> IntervalStream<C> {
+    /// cancels the interval timer, the stream ends on its next poll.
+    pub fn stop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.wheel.borrow_mut().drop(id);
+        }
+    }
+}
+
+impl<C: Clock> Stream for IntervalStream<C> {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let Some(id) = self.id else {
+            return Poll::Ready(None);
+        };
+        // the wheel re-arms the timer on its own, a ready poll consumes one fire
+        self.wheel.borrow_mut().poll(id, cx.waker()).map(Some)
+    }
+}
+
+impl<C: Clock> Drop for IntervalStream<C> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
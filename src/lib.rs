@@ -1,37 +1,304 @@
-use crate::slab::TimerStorage;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use crate::slab::{Timers, WakeOutcome, Wakeup};
+use alloc::{boxed::Box, vec::Vec};
+pub use cell::CellTimeWheel;
+#[cfg(feature = "std")]
+pub use clock::StdClock;
+pub use clock::{Clock, MockClock, TickClock};
+use core::{fmt, future::Future, task::Waker, time::Duration};
+pub use expired::Expired;
+#[cfg(feature = "futures")]
+pub use interval::IntervalStream;
+#[cfg(feature = "driver")]
+pub use local::block_on;
+#[cfg(feature = "std")]
+pub use local::{local_wheel, sleep, try_sleep};
+#[cfg(feature = "std")]
+pub use shared::{SharedTimeWheel, TimerHandle};
+pub use slab::{Timer, TimerStorage, TimerStore};
+pub use sleep::Sleep;
 use smallvec::SmallVec;
-use std::{
-    task::Waker,
-    time::{Duration, Instant},
-};
+pub use snapshot::{Snapshot, SnapshotTimer};
+pub use token::CancelToken;
+
+// trace level diagnostics, compiled out unless the `log` feature is enabled
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+    };
+}
 
+mod cell;
+mod clock;
+mod expired;
+#[cfg(feature = "futures")]
+mod interval;
+#[cfg(feature = "std")]
+mod local;
+#[cfg(feature = "std")]
+mod shared;
 mod slab;
+mod sleep;
+mod snapshot;
+mod token;
 
-const MS_TICK: u64 = 10; //10ms
-const MS_BUCKETS: usize = 20; //200ms
+const MS_TICK: u64 = 10; //10ms, default tick
+const MS_BUCKETS: usize = 100; //1s
 const S_BUCKETS: usize = 60;
+const M_BUCKETS: usize = 60;
 const H_BUCKETS: usize = 24;
 const MAX_DURATION_HOURS: u64 = 24;
-const SMALLVEC_SIZE: usize = 8;
+// ticks covered by one bucket of the second, minute and hour levels
+const S_SPAN: u64 = MS_BUCKETS as u64;
+const M_SPAN: u64 = S_SPAN * S_BUCKETS as u64;
+const H_SPAN: u64 = M_SPAN * M_BUCKETS as u64;
+// ticks covered by a full rotation of the hour level
+const WHEEL_SPAN: u64 = H_SPAN * H_BUCKETS as u64;
 
-type TimerId = usize;
-type Bucket = SmallVec<[TimerId; SMALLVEC_SIZE]>;
+/// handle to a timer. slots are reused once a timer is released, the generation tells a stale
+/// handle apart from the timer now occupying its slot, and such a handle acts as an already
+/// gone timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerId {
+    pub index: usize,
+    pub generation: u32,
+}
+
+// buckets hold the slot index of their timers
+type Bucket<const N: usize> = SmallVec<[usize; N]>;
 
+/// clock used by [`TimeWheel::new`], [`StdClock`] unless the `std` feature is disabled.
+#[cfg(feature = "std")]
+pub type DefaultClock = StdClock;
+#[cfg(not(feature = "std"))]
+pub type DefaultClock = MockClock;
+
+/// a duration over the limit of the wheel, see [`TimeWheel::max_duration`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct DurationTooLong;
+pub struct DurationTooLong {
+    pub requested: Duration,
+    pub max: Duration,
+}
 
-pub struct TimeWheel {
-    storage: TimerStorage,
-    buckets: BucketLevels,
-    last_tick: Instant,
+impl fmt::Display for DurationTooLong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "duration of {:?} is not under the {:?} limit of the wheel",
+            self.requested, self.max
+        )
+    }
+}
+
+impl core::error::Error for DurationTooLong {}
+
+/// how durations are rounded to whole ticks when a timer is armed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingPolicy {
+    /// rounds down, the default.
+    #[default]
+    Floor,
+    /// rounds up, timers fire up to a tick later but never before their full duration elapsed.
+    Ceil,
+    /// rounds to the closest tick.
+    Nearest,
+}
+
+/// how interval timers catch up when the wheel is advanced over several of their periods.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// fires once per missed period, the default: every occurrence can be accounted for with
+    /// one poll each, as if the wheel had been ticked on time.
+    #[default]
+    Burst,
+    /// fires once and re-arms one period after the current time, the missed periods are dropped.
+    Coalesce,
+}
+
+/// lifecycle of a timer, as reported by [`TimeWheel::state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerState {
+    /// waiting to fire, interval timers stay pending until cancelled.
+    Pending,
+    /// fired, the slot is kept until the timer is dropped.
+    Fired,
+}
+
+/// what [`TimeWheel::drop`] did with a timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropOutcome {
+    /// the timer was waiting and won't fire.
+    Cancelled,
+    /// the timer fired before being dropped, only its slot was released.
+    AlreadyFired,
+    /// the id is unknown or stale, nothing was done.
+    Unknown,
+}
+
+/// outcome of [`TimeWheel::poll_remaining`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollRemaining {
+    Ready,
+    /// the timer is waiting, with the duration until it is triggered.
+    Pending(Duration),
+}
+
+/// outcome of [`TimeWheel::tick_bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickOutcome {
+    /// timers woken by this call.
+    pub fired: usize,
+    /// the fire cap was reached with timers still due, tick again to fire them.
+    pub more: bool,
+}
+
+/// position of the hands of a wheel, taken with [`TimeWheel::position`] and put back with
+/// [`TimeWheel::restore_position`]. the timers are not part of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WheelPosition<I> {
+    ticks: u64,
+    last_tick: I,
+}
+
+/// reasons a timer can't be armed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimerError {
+    /// the duration reaches the 24h limit of the wheel.
+    DurationTooLong(DurationTooLong),
+    /// arming would exceed the wheel's maximum number of live timers, or its fixed number of slots.
+    TooManyTimers,
+    /// the timer is still waiting to fire.
+    StillPending,
+    /// the id is unknown or stale, its slot was released.
+    UnknownTimer,
+}
+
+impl fmt::Display for TimerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerError::DurationTooLong(err) => err.fmt(f),
+            TimerError::TooManyTimers => f.write_str("the wheel holds too many timers"),
+            TimerError::StillPending => f.write_str("the timer is still waiting to fire"),
+            TimerError::UnknownTimer => f.write_str("the timer is unknown or was released"),
+        }
+    }
+}
+
+impl core::error::Error for TimerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            TimerError::DurationTooLong(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DurationTooLong> for TimerError {
+    fn from(err: DurationTooLong) -> Self {
+        TimerError::DurationTooLong(err)
+    }
+}
+
+/// distribution of the waiting timers over the levels of the wheel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWheelStats {
+    pub ms_timers: usize,
+    pub s_timers: usize,
+    pub m_timers: usize,
+    pub h_timers: usize,
+    pub occupied_ms_buckets: usize,
+    pub occupied_s_buckets: usize,
+    pub occupied_m_buckets: usize,
+    pub occupied_h_buckets: usize,
+}
+
+/// hierarchical timer wheel, each timer can carry a payload of type `T` retrieved with
+/// [`TimeWheel::take_payload`] once it fired.
+///
+/// every bucket stores up to `N` timers inline before spilling to the heap, raising it keeps
+/// buckets inline when many timers share a bucket at the cost of a larger wheel.
+///
+/// timers are kept in `S`, a [`TimerStorage`] unless another [`TimerStore`] backend is picked
+/// with [`TimeWheel::from_clock`].
+///
+/// cloning a wheel forks its schedule: the clone holds the same timers with the same ids and
+/// both wheels fire them independently, waking the same tasks. callbacks can only run once and
+/// stay with the original wheel, the clone fires their timers without running anything.
+/// the clock is cloned as well, clones of a [`MockClock`] share their time.
+#[derive(Clone)]
+pub struct TimeWheel<
+    T = (),
+    C: Clock = DefaultClock,
+    const N: usize = 8,
+    S: TimerStore<T> = TimerStorage<T>,
+> {
+    storage: Timers<T, S>,
+    buckets: BucketLevels<N>,
+    tick_ms: u64,
+    // placement of each waiting timer, indexed by timer id
+    placements: Vec<Placement<C::Instant>>,
+    // ticks processed since the wheel was created
+    ticks: u64,
+    clock: C,
+    last_tick: C::Instant,
     current_ms_idx: usize,
     current_s_idx: usize,
+    current_m_idx: usize,
     current_h_idx: usize,
+    paused: bool,
+    max_timers: Option<usize>,
+    // number of slots allocated upfront and never grown
+    fixed_capacity: Option<usize>,
+    max_fires_per_tick: Option<usize>,
+    coalesce_wakes: bool,
+    catch_up: CatchUpPolicy,
+    rounding: RoundingPolicy,
+    fired_last_tick: bool,
+}
+
+/// level of the wheel holding a timer, from the finest to the coarsest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// one bucket per tick, up to the end of the current second level bucket.
+    Ms,
+    /// one bucket per [`TimeWheel::ms_level_span`], a second at the default tick.
+    Second,
+    /// one bucket per [`TimeWheel::s_level_span`], a minute at the default tick.
+    Minute,
+    /// one bucket per [`TimeWheel::m_level_span`], an hour at the default tick. the bucket under
+    /// the hand holds the timers of its hour in the next rotation, and with a tick under 10ms
+    /// the level spans less than the 24h limit so any bucket may hold later rotations.
+    Hour,
 }
 
+/// bucket holding a timer, as reported by [`TimeWheel::classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketLocation {
+    pub level: Level,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Placement<I> {
+    location: BucketLocation,
+    // tick at which the timer fires, its bucket is drained when `ticks` reaches it
+    deadline: u64,
+    // instant the timer is due, a timer drained before it is placed again instead of firing
+    target: I,
+    // instant of the tick that last fired the timer, kept when the timer is placed again
+    fired_at: I,
+}
+
+#[derive(Clone)]
 struct Bitset<T>(T);
 
-impl Bitset<u16> {
+impl Bitset<u32> {
     #[inline]
     fn set(&mut self, idx: usize) {
         self.0 |= 1 << idx;
@@ -46,9 +313,14 @@ impl Bitset<u16> {
     fn is_set(&self, idx: usize) -> bool {
         (self.0 & (1 << idx)) != 0
     }
+
+    #[inline]
+    fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
 }
 
-impl Bitset<u32> {
+impl Bitset<u64> {
     #[inline]
     fn set(&mut self, idx: usize) {
         self.0 |= 1 << idx;
@@ -63,9 +335,14 @@ impl Bitset<u32> {
     fn is_set(&self, idx: usize) -> bool {
         (self.0 & (1 << idx)) != 0
     }
+
+    #[inline]
+    fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
 }
 
-impl Bitset<u64> {
+impl Bitset<u128> {
     #[inline]
     fn set(&mut self, idx: usize) {
         self.0 |= 1 << idx;
@@ -80,188 +357,1655 @@ impl Bitset<u64> {
     fn is_set(&self, idx: usize) -> bool {
         (self.0 & (1 << idx)) != 0
     }
+
+    #[inline]
+    fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
 }
 
-struct BucketLevels {
-    ms_level: [Bucket; MS_BUCKETS],
-    s_level: [Bucket; S_BUCKETS],
-    h_level: [Bucket; H_BUCKETS],
-    ms_occupied: Bitset<u32>,
+#[derive(Clone)]
+struct BucketLevels<const N: usize> {
+    ms_level: [Bucket<N>; MS_BUCKETS],
+    s_level: [Bucket<N>; S_BUCKETS],
+    m_level: [Bucket<N>; M_BUCKETS],
+    h_level: [Bucket<N>; H_BUCKETS],
+    ms_occupied: Bitset<u128>,
     s_occupied: Bitset<u64>,
+    m_occupied: Bitset<u64>,
     h_occupied: Bitset<u32>,
 }
 
-impl BucketLevels {
+impl<const N: usize> BucketLevels<N> {
     fn new() -> Self {
         Self {
-            ms_level: std::array::from_fn(|_| SmallVec::new()),
-            s_level: std::array::from_fn(|_| SmallVec::new()),
-            h_level: std::array::from_fn(|_| SmallVec::new()),
+            ms_level: core::array::from_fn(|_| SmallVec::new()),
+            s_level: core::array::from_fn(|_| SmallVec::new()),
+            m_level: core::array::from_fn(|_| SmallVec::new()),
+            h_level: core::array::from_fn(|_| SmallVec::new()),
             ms_occupied: Bitset(0),
             s_occupied: Bitset(0),
+            m_occupied: Bitset(0),
             h_occupied: Bitset(0),
         }
     }
+
+    fn push(&mut self, location: BucketLocation, id: usize) {
+        let index = location.index;
+        match location.level {
+            Level::Ms => {
+                self.ms_occupied.set(index);
+                self.ms_level[index].push(id);
+            }
+            Level::Second => {
+                self.s_occupied.set(index);
+                self.s_level[index].push(id);
+            }
+            Level::Minute => {
+                self.m_occupied.set(index);
+                self.m_level[index].push(id);
+            }
+            Level::Hour => {
+                self.h_occupied.set(index);
+                self.h_level[index].push(id);
+            }
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.ms_level
+            .iter()
+            .chain(&self.s_level)
+            .chain(&self.m_level)
+            .chain(&self.h_level)
+            .flat_map(|bucket| bucket.iter().copied())
+    }
+
+    /// empties every bucket, keeping their allocations.
+    fn clear(&mut self) {
+        for bucket in self
+            .ms_level
+            .iter_mut()
+            .chain(&mut self.s_level)
+            .chain(&mut self.m_level)
+            .chain(&mut self.h_level)
+        {
+            bucket.clear();
+        }
+        self.ms_occupied = Bitset(0);
+        self.s_occupied = Bitset(0);
+        self.m_occupied = Bitset(0);
+        self.h_occupied = Bitset(0);
+    }
+
+    /// releases the spilled allocations, buckets holding at most `N` timers go back inline.
+    fn shrink_to_fit(&mut self) {
+        for bucket in self
+            .ms_level
+            .iter_mut()
+            .chain(&mut self.s_level)
+            .chain(&mut self.m_level)
+            .chain(&mut self.h_level)
+        {
+            bucket.shrink_to_fit();
+        }
+    }
+
+    fn bucket_mut(&mut self, location: BucketLocation) -> &mut Bucket<N> {
+        match location.level {
+            Level::Ms => &mut self.ms_level[location.index],
+            Level::Second => &mut self.s_level[location.index],
+            Level::Minute => &mut self.m_level[location.index],
+            Level::Hour => &mut self.h_level[location.index],
+        }
+    }
+
+    /// moves a whole bucket to an empty location.
+    fn put(&mut self, location: BucketLocation, bucket: Bucket<N>) {
+        debug_assert!(self.bucket_mut(location).is_empty());
+        match location.level {
+            Level::Ms => self.ms_occupied.set(location.index),
+            Level::Second => self.s_occupied.set(location.index),
+            Level::Minute => self.m_occupied.set(location.index),
+            Level::Hour => self.h_occupied.set(location.index),
+        }
+        *self.bucket_mut(location) = bucket;
+    }
+
+    /// hands the allocation of a taken bucket back to its location if nothing was pushed there since.
+    fn restore(&mut self, location: BucketLocation, mut bucket: Bucket<N>) {
+        let slot = self.bucket_mut(location);
+        if slot.is_empty() {
+            bucket.clear();
+            *slot = bucket;
+        }
+    }
+
+    fn take(&mut self, location: BucketLocation) -> Bucket<N> {
+        let index = location.index;
+        match location.level {
+            Level::Ms => {
+                self.ms_occupied.clear(index);
+                core::mem::take(&mut self.ms_level[index])
+            }
+            Level::Second => {
+                self.s_occupied.clear(index);
+                core::mem::take(&mut self.s_level[index])
+            }
+            Level::Minute => {
+                self.m_occupied.clear(index);
+                core::mem::take(&mut self.m_level[index])
+            }
+            Level::Hour => {
+                self.h_occupied.clear(index);
+                core::mem::take(&mut self.h_level[index])
+            }
+        }
+    }
+
+    fn remove(&mut self, location: BucketLocation, id: usize) {
+        let index = location.index;
+        let bucket = self.bucket_mut(location);
+
+        if let Some(pos) = bucket.iter().position(|&t| t == id) {
+            bucket.remove(pos);
+        }
+
+        if bucket.is_empty() {
+            match location.level {
+                Level::Ms => self.ms_occupied.clear(index),
+                Level::Second => self.s_occupied.clear(index),
+                Level::Minute => self.m_occupied.clear(index),
+                Level::Hour => self.h_occupied.clear(index),
+            }
+        }
+    }
+
+    /// keeps only the timers for which `keep` returns true, clearing the bits of emptied buckets.
+    fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        for (index, bucket) in self.ms_level.iter_mut().enumerate() {
+            bucket.retain(|id| keep(*id));
+            if bucket.is_empty() {
+                self.ms_occupied.clear(index);
+            }
+        }
+        for (index, bucket) in self.s_level.iter_mut().enumerate() {
+            bucket.retain(|id| keep(*id));
+            if bucket.is_empty() {
+                self.s_occupied.clear(index);
+            }
+        }
+        for (index, bucket) in self.m_level.iter_mut().enumerate() {
+            bucket.retain(|id| keep(*id));
+            if bucket.is_empty() {
+                self.m_occupied.clear(index);
+            }
+        }
+        for (index, bucket) in self.h_level.iter_mut().enumerate() {
+            bucket.retain(|id| keep(*id));
+            if bucket.is_empty() {
+                self.h_occupied.clear(index);
+            }
+        }
+    }
 }
 
 impl TimeWheel {
     #[must_use]
     pub fn new() -> Self {
+        Self::new_with_payload()
+    }
+
+    /// creates a wheel advancing by `tick` instead of the default 10ms, see
+    /// [`TimeWheel::with_tick`].
+    #[must_use]
+    pub fn new_with_tick(tick: Duration) -> Self {
+        Self::new().with_tick(tick)
+    }
+
+    /// creates a wheel sized for `capacity` concurrently live timers, the capacity is only a hint
+    /// and the wheel still grows past it.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut wheel = Self::new();
+        wheel.reserve(capacity);
+        wheel
+    }
+}
+
+impl<T> TimeWheel<T> {
+    /// creates a wheel whose timers carry a payload of type `T`.
+    #[must_use]
+    pub fn new_with_payload() -> Self {
+        Self::from_clock(DefaultClock::default())
+    }
+}
+
+impl<C: Clock> TimeWheel<(), C> {
+    /// creates a wheel reading the time from `clock`.
+    #[must_use]
+    pub fn new_with_clock(clock: C) -> Self {
+        Self::from_clock(clock)
+    }
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> TimeWheel<T, C, N, S> {
+    /// creates a wheel with every parameter chosen by the caller, for combinations the other
+    /// constructors don't cover such as a payload with a custom clock or another bucket size.
+    #[must_use]
+    pub fn from_clock(clock: C) -> Self {
+        Self::from_parts(clock, S::default())
+    }
+
+    /// same as [`TimeWheel::from_clock`] with an already configured storage backend, which must
+    /// not hold any timer yet.
+    ///
+    /// # Panics
+    ///
+    /// panics if `store` is not empty.
+    #[must_use]
+    pub fn from_parts(clock: C, store: S) -> Self {
+        assert!(store.is_empty(), "the store of a new wheel must be empty");
         Self {
-            storage: TimerStorage::default(),
+            storage: Timers::from_store(store),
             buckets: BucketLevels::new(),
-            last_tick: Instant::now(),
+            tick_ms: MS_TICK,
+            placements: Vec::new(),
+            ticks: 0,
+            last_tick: clock.now(),
+            clock,
             current_ms_idx: 0,
             current_s_idx: 0,
+            current_m_idx: 0,
             current_h_idx: 0,
+            paused: false,
+            max_timers: None,
+            fixed_capacity: None,
+            max_fires_per_tick: None,
+            coalesce_wakes: false,
+            catch_up: CatchUpPolicy::Burst,
+            fired_last_tick: false,
+            rounding: RoundingPolicy::Floor,
         }
     }
 
-    pub fn tick(&mut self) {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_tick);
-        let ticks_to_process = (elapsed.as_millis() / MS_TICK as u128) as usize;
+    /// advances the wheel by `tick` instead of the default 10ms, the tick is truncated to whole
+    /// milliseconds and must be at least 1ms. meant to be set before arming any timer.
+    /// a smaller tick gives more precise timers but means more tick iterations per second to
+    /// process in [`TimeWheel::tick`]. every level keeps its number of buckets so their spans
+    /// shrink with the tick, while [`TimeWheel::max_duration`] stays at 24h: at a 1ms tick the
+    /// hour level spans 2.4h and longer timers go around it several times before firing.
+    #[must_use]
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        let tick_ms = tick.as_millis() as u64;
+        assert!(tick_ms >= 1, "tick must be at least 1ms");
+        self.tick_ms = tick_ms;
+        self
+    }
+
+    /// caps the number of live timers, arming more fails with [`TimerError::TooManyTimers`].
+    /// fired timers don't count toward the cap. wheels are unlimited by default.
+    #[must_use]
+    pub fn with_max_timers(mut self, max_timers: usize) -> Self {
+        self.max_timers = Some(max_timers);
+        self
+    }
+
+    /// allocates `capacity` timer slots upfront and never grows them, arming a timer while every
+    /// slot is taken fails with [`TimerError::TooManyTimers`]. fired timers hold their slot until
+    /// dropped, dropped slots are reused. buckets keep up to `N` timers inline, a bucket holding
+    /// more still allocates so `N` has to be sized for the busiest bucket when allocating after
+    /// startup is not an option.
+    #[must_use]
+    pub fn with_fixed_capacity(mut self, capacity: usize) -> Self {
+        self.storage.reserve(capacity);
+        self.placements.reserve_exact(capacity);
+        self.fixed_capacity = Some(capacity);
+        self
+    }
+
+    /// caps the number of timers a single tick or advance fires, the ones left stay due and
+    /// fire on the next call. unlimited by default, [`TimeWheel::tick_bounded`] tells whether
+    /// timers were left.
+    #[must_use]
+    pub fn with_max_fires_per_tick(mut self, max_fires: usize) -> Self {
+        self.max_fires_per_tick = Some(max_fires);
+        self
+    }
+
+    /// when enabled, timers firing from the same bucket wake a task once even if several of
+    /// them were polled with its waker. every timer still fires and is ready on its next poll,
+    /// only the redundant wakeups are skipped. disabled by default.
+    #[must_use]
+    pub fn with_wake_coalescing(mut self, enabled: bool) -> Self {
+        self.coalesce_wakes = enabled;
+        self
+    }
+
+    /// sets how interval timers catch up on periods missed by a late tick,
+    /// [`CatchUpPolicy::Burst`] by default.
+    #[must_use]
+    pub fn with_catch_up(mut self, catch_up: CatchUpPolicy) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    /// sets how durations are rounded to the wheel's tick, [`RoundingPolicy::Floor`] by default.
+    #[must_use]
+    pub fn with_rounding(mut self, rounding: RoundingPolicy) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// fires the timers due since the last tick, returns how many were woken.
+    /// timers sharing a deadline fire in the order they were armed, and a timer never fires
+    /// before its duration elapsed on the wheel's clock, even if the wheel was lagging when armed.
+    pub fn tick(&mut self) -> usize {
+        self.advance_to(self.clock.now())
+    }
+
+    /// same as [`TimeWheel::tick`] but also reports whether the fire cap set with
+    /// [`TimeWheel::with_max_fires_per_tick`] left due timers behind, so the caller can yield
+    /// before ticking again.
+    pub fn tick_bounded(&mut self) -> TickOutcome {
+        let mut fired = 0;
+        let more = self.advance_with(self.clock.now(), |_, wakeup| {
+            wakeup.wake();
+            fired += 1;
+        });
+        TickOutcome { fired, more }
+    }
+
+    /// same as [`TimeWheel::tick`] but returns the ids of the timers woken by this call.
+    pub fn tick_collect(&mut self) -> Vec<TimerId> {
+        self.advance_to_collect(self.clock.now())
+    }
+
+    /// advances the wheel to `now` as [`TimeWheel::tick`] does with the current time,
+    /// an instant older than the last advance is treated as no time elapsed.
+    pub fn advance_to(&mut self, now: C::Instant) -> usize {
+        let mut fired = 0;
+        self.advance_with(now, |_, wakeup| {
+            wakeup.wake();
+            fired += 1;
+        });
+        fired
+    }
+
+    /// same as [`TimeWheel::advance_to`] but returns the ids of the timers woken by this call.
+    pub fn advance_to_collect(&mut self, now: C::Instant) -> Vec<TimerId> {
+        let mut fired = Vec::new();
+        self.advance_with(now, |id, wakeup| {
+            wakeup.wake();
+            fired.push(id);
+        });
+        fired
+    }
+
+    /// advances the wheel like [`TimeWheel::advance_to`] but hands back the wakers and callbacks of
+    /// the fired timers instead of running them, so they run once a lock around the wheel is released.
+    pub(crate) fn advance_deferred(&mut self, now: C::Instant) -> Vec<Wakeup> {
+        let mut wakeups = Vec::new();
+        self.advance_with(now, |_, wakeup| wakeups.push(wakeup));
+        wakeups
+    }
+
+    /// returns true if the fire cap stopped the advance before `now`.
+    fn advance_with(&mut self, now: C::Instant, mut on_fire: impl FnMut(TimerId, Wakeup)) -> bool {
+        let budget = self.max_fires_per_tick.unwrap_or(usize::MAX);
+        let mut fired_any = false;
+        let more = self.advance_capped(now, budget, |id, wakeup| {
+            fired_any = true;
+            on_fire(id, wakeup);
+        });
+        self.fired_last_tick = fired_any;
+        more
+    }
 
-        for _ in 0..ticks_to_process {
-            self.process_single_tick();
+    /// advances the wheel to `now` firing at most `budget` timers, returns true if it stopped early.
+    fn advance_capped(
+        &mut self,
+        now: C::Instant,
+        mut budget: usize,
+        mut on_fire: impl FnMut(TimerId, Wakeup),
+    ) -> bool {
+        if self.paused {
+            return false;
         }
 
-        self.last_tick = now;
+        let elapsed = C::duration_between(self.last_tick, now);
+        let ticks_to_process = (elapsed.as_millis() / self.tick_ms as u128) as u64;
+        let start_tick = self.ticks;
+        let end_tick = self.ticks + ticks_to_process;
+
+        while self.ticks < end_tick {
+            // ticks with nothing to fire or cascade are skipped in one go
+            let next = self.next_busy_tick().min(end_tick);
+            if next > self.ticks {
+                self.move_hands(next);
+            }
+            let tick_at = C::add(
+                self.last_tick,
+                Duration::from_millis((self.ticks + 1 - start_tick) * self.tick_ms),
+            );
+            if self.ticks < end_tick
+                && !self.process_single_tick(now, tick_at, end_tick, &mut budget, &mut on_fire)
+            {
+                // resume from the unfinished tick, the time left is processed by the next call
+                let processed = Duration::from_millis((self.ticks - start_tick) * self.tick_ms);
+                self.last_tick = C::add(self.last_tick, processed);
+                return true;
+            }
+        }
+
+        // the time past the last whole tick is kept for the next advance instead of being lost
+        let processed = Duration::from_millis(ticks_to_process * self.tick_ms);
+        self.last_tick = C::add(self.last_tick, processed);
+        false
     }
 
-    fn process_single_tick(&mut self) {
+    /// processes the tick `ticks`, ending at `tick_at`, as part of an advance to `now` which ends
+    /// at `end_tick`.
+    /// returns false if `budget` ran out before the bucket was drained, the tick is then left
+    /// unfinished with the remaining timers still in their bucket.
+    fn process_single_tick(
+        &mut self,
+        now: C::Instant,
+        tick_at: C::Instant,
+        end_tick: u64,
+        budget: &mut usize,
+        on_fire: &mut impl FnMut(TimerId, Wakeup),
+    ) -> bool {
         if self.buckets.ms_occupied.is_set(self.current_ms_idx) {
-            self.buckets.ms_occupied.clear(self.current_ms_idx);
+            if *budget == 0 {
+                return false;
+            }
+
+            let mut bucket = core::mem::take(&mut self.buckets.ms_level[self.current_ms_idx]);
+            // the sort is stable, timers of equal priority stay in arming order
+            if self.storage.has_priorities()
+                && bucket.iter().any(|&slot| self.storage.priority(slot) > 0)
+            {
+                bucket.sort_by_key(|&slot| core::cmp::Reverse(self.storage.priority(slot)));
+            }
+            let mut drained = 0;
+            // wakers notified by this bucket, only tracked when coalescing
+            let mut woken: Vec<Waker> = Vec::new();
+            for &slot in bucket.iter() {
+                if *budget == 0 {
+                    break;
+                }
+                drained += 1;
+
+                let target = self.placements[slot].target;
+                if target > now {
+                    // armed while the wheel lagged behind the clock, the tick came too early
+                    self.place(slot, self.deadline_after(now, end_tick, target), target);
+                    continue;
+                }
 
-            for timer_id in self.buckets.ms_level[self.current_ms_idx].drain(..) {
-                self.storage.wake(timer_id);
+                let timer_id = self.storage.id(slot);
+                let Some((mut wakeup, outcome)) = self.storage.wake(slot) else {
+                    continue;
+                };
+                if let (true, Wakeup::Waker(waker)) = (self.coalesce_wakes, &wakeup) {
+                    if woken.iter().any(|other| other.will_wake(waker)) {
+                        wakeup = Wakeup::Coalesced;
+                    } else {
+                        woken.push(waker.clone());
+                    }
+                }
+                *budget -= 1;
+                // a timer armed while the wheel lagged is drained by a tick ending before its
+                // target, it is reported as fired at the target instead
+                self.placements[slot].fired_at = core::cmp::max(tick_at, target);
+                on_fire(timer_id, wakeup);
+                // a period is at least one tick, periodic timers never land back in this bucket
+                if let WakeOutcome::Reschedule(step) = outcome {
+                    let step_duration = Duration::from_millis(step * self.tick_ms);
+                    let deadline = self.placements[slot].deadline + step;
+                    if self.catch_up == CatchUpPolicy::Coalesce && deadline < end_tick {
+                        // due again within this advance, re-armed from the end of it instead
+                        self.place(slot, end_tick + step, C::add(now, step_duration));
+                    } else {
+                        self.place(slot, deadline, C::add(target, step_duration));
+                    }
+                }
+            }
+            bucket.drain(..drained);
+            let unfinished = !bucket.is_empty();
+            if !unfinished {
+                self.buckets.ms_occupied.clear(self.current_ms_idx);
+            }
+            // hand the allocation back to the drained bucket
+            self.buckets.ms_level[self.current_ms_idx] = bucket;
+            if unfinished {
+                return false;
             }
         }
 
-        self.current_ms_idx = (self.current_ms_idx + 1) % MS_BUCKETS;
+        self.ticks += 1;
+        self.current_ms_idx = (self.ticks % S_SPAN) as usize;
 
         if self.current_ms_idx == 0 {
-            self.cascade_from_seconds();
-            self.current_s_idx = (self.current_s_idx + 1) % S_BUCKETS;
+            self.current_s_idx = ((self.ticks / S_SPAN) % S_BUCKETS as u64) as usize;
 
+            // coarsest first, timers may land in the finer bucket cascaded right after
             if self.current_s_idx == 0 {
-                self.cascade_from_hours();
-                self.current_h_idx = (self.current_h_idx + 1) % H_BUCKETS;
+                self.current_m_idx = ((self.ticks / M_SPAN) % M_BUCKETS as u64) as usize;
+
+                if self.current_m_idx == 0 {
+                    self.current_h_idx = ((self.ticks / H_SPAN) % H_BUCKETS as u64) as usize;
+                    self.cascade(BucketLocation {
+                        level: Level::Hour,
+                        index: self.current_h_idx,
+                    });
+                }
+
+                self.cascade(BucketLocation {
+                    level: Level::Minute,
+                    index: self.current_m_idx,
+                });
             }
+
+            self.cascade(BucketLocation {
+                level: Level::Second,
+                index: self.current_s_idx,
+            });
+        }
+        true
+    }
+
+    /// sleeps on the wheel's clock until the next deadline and ticks, until no timer is left.
+    /// it never returns while an interval timer is armed.
+    pub fn run_until_idle(&mut self) {
+        while let Some(duration) = self.next_deadline() {
+            self.clock.sleep(duration);
+            self.tick();
+        }
+    }
+
+    /// same as [`TimeWheel::run_until_idle`] for async code, waiting with the future returned
+    /// by `sleep` instead of blocking on the clock.
+    pub async fn run_until_idle_with<F: Future<Output = ()>>(
+        &mut self,
+        mut sleep: impl FnMut(Duration) -> F,
+    ) {
+        while let Some(duration) = self.next_deadline() {
+            sleep(duration).await;
+            self.tick();
+        }
+    }
+
+    /// stops the wheel, ticking it does nothing until [`TimeWheel::resume`] is called.
+    /// timers do not advance while paused, the time spent paused is added to all of them.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// restarts a paused wheel from the current time, the paused period is skipped instead of
+    /// replayed on the next tick.
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.last_tick = self.clock.now();
         }
     }
 
-    fn cascade_from_seconds(&mut self) {
-        if !self.buckets.s_occupied.is_set(self.current_s_idx) {
-            return;
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// first tick at which a timer due at `target` can fire without firing early, for a wheel
+    /// being advanced to `now` up to `end_tick`, once `now` is the instant of `end_tick`.
+    fn deadline_after(&self, now: C::Instant, end_tick: u64, target: C::Instant) -> u64 {
+        let tick_ns = u128::from(self.tick_ms) * 1_000_000;
+        let left = C::duration_between(now, target)
+            .as_nanos()
+            .div_ceil(tick_ns) as u64;
+        // the tick `end_tick + n - 1` is processed once n ticks elapsed after `now`
+        (end_tick + left).saturating_sub(1).max(self.ticks + 1)
+    }
+
+    /// moves every timer of the bucket to the level matching its remaining ticks.
+    /// a bucket only receives timers armed directly into it once the rotation it covers started,
+    /// which is after it was cascaded into, so appending keeps every bucket in arming order.
+    fn cascade(&mut self, location: BucketLocation) {
+        let mut bucket = self.buckets.take(location);
+
+        // timers sharing a destination, typically same period timers, move with their buffer
+        if let Some(&first) = bucket.first() {
+            let destination = self.location_for(self.placements[first].deadline);
+            if destination != location
+                && self.buckets.bucket_mut(destination).is_empty()
+                && bucket
+                    .iter()
+                    .all(|&id| self.location_for(self.placements[id].deadline) == destination)
+            {
+                for &id in &bucket {
+                    self.placements[id].location = destination;
+                }
+                self.buckets.put(destination, bucket);
+                return;
+            }
+        }
+
+        for id in bucket.drain(..) {
+            let location = self.location_for(self.placements[id].deadline);
+            self.placements[id].location = location;
+            self.buckets.push(location, id);
         }
+        self.buckets.restore(location, bucket);
+    }
 
-        self.buckets.s_occupied.clear(self.current_s_idx);
+    /// bucket currently holding a waiting timer, None once it fired or was dropped.
+    pub fn location(&self, id: TimerId) -> Option<BucketLocation> {
+        self.storage
+            .is_pending(id)
+            .then(|| self.placements[id.index].location)
+    }
 
-        let bucket = std::mem::take(&mut self.buckets.s_level[self.current_s_idx]);
-        self.buckets.ms_occupied.set(self.current_ms_idx);
-        self.buckets.ms_level[self.current_ms_idx].extend(bucket);
+    /// a deadline goes in the finest level whose current rotation contains it.
+    fn location_for(&self, deadline: u64) -> BucketLocation {
+        if deadline / S_SPAN == self.ticks / S_SPAN {
+            BucketLocation {
+                level: Level::Ms,
+                index: (deadline % S_SPAN) as usize,
+            }
+        } else if deadline / M_SPAN == self.ticks / M_SPAN {
+            BucketLocation {
+                level: Level::Second,
+                index: ((deadline / S_SPAN) % S_BUCKETS as u64) as usize,
+            }
+        } else if deadline / H_SPAN == self.ticks / H_SPAN {
+            BucketLocation {
+                level: Level::Minute,
+                index: ((deadline / M_SPAN) % M_BUCKETS as u64) as usize,
+            }
+        } else {
+            // cascaded when the hour starts, a later rotation is placed in the same bucket again
+            BucketLocation {
+                level: Level::Hour,
+                index: ((deadline / H_SPAN) % H_BUCKETS as u64) as usize,
+            }
+        }
     }
 
-    fn cascade_from_hours(&mut self) {
-        if !self.buckets.h_occupied.is_set(self.current_h_idx) {
-            return;
+    /// bucket a timer armed now for `duration` would be placed in, without arming it.
+    /// returns None for a duration the wheel rejects and for a zero duration, which is never placed.
+    pub fn classify(&self, duration: Duration) -> Option<BucketLocation> {
+        if duration.is_zero() {
+            return None;
         }
+        let total_ms = Self::checked_ms(duration).ok()?;
+        Some(self.location_for(self.deadline_from_ms(total_ms)))
+    }
 
-        self.buckets.h_occupied.clear(self.current_h_idx);
+    /// returns `Ready` once the timer fired, right away for a zero duration timer, and stores
+    /// `waker` to be woken otherwise. same as [`TimeWheel::poll_at`] with the clock's time.
+    pub fn poll(&mut self, id: TimerId, waker: &Waker) -> core::task::Poll<()> {
+        // the clock is read for every waiting one shot timer, other timers never need it
+        self.poll_with(id, waker, C::now)
+    }
 
-        let bucket = std::mem::take(&mut self.buckets.h_level[self.current_h_idx]);
-        self.buckets.s_occupied.set(self.current_s_idx);
-        self.buckets.s_level[self.current_s_idx].extend(bucket);
+    /// same as [`TimeWheel::poll`] but a one shot timer whose target is reached at `now` is ready
+    /// even if the wheel did not tick up to it yet, it is then completed without being woken.
+    /// `now` must not go backwards compared to the instants given to the wheel's clock and ticks.
+    pub fn poll_at(&mut self, id: TimerId, waker: &Waker, now: C::Instant) -> core::task::Poll<()> {
+        self.poll_with(id, waker, |_| now)
     }
 
-    fn compute_ms_bucket_from_ms(&self, ms: u64) -> usize {
-        let bucket_offset = (ms / MS_TICK) as usize;
-        (self.current_ms_idx + bucket_offset.min(MS_BUCKETS - 1)) % MS_BUCKETS
+    fn poll_with(
+        &mut self,
+        id: TimerId,
+        waker: &Waker,
+        now: impl FnOnce(&C) -> C::Instant,
+    ) -> core::task::Poll<()> {
+        let target = self
+            .placements
+            .get(id.index)
+            .map(|placement| placement.target);
+        let clock = &self.clock;
+        let mut polled_at = None;
+        let due = || {
+            let now = now(clock);
+            polled_at = Some(now);
+            target.is_some_and(|target| target <= now)
+        };
+        match self.storage.poll_due(id, waker, due) {
+            Some(poll) => poll,
+            None => {
+                // fired by the poll, the caller is told right away and nothing is woken
+                self.buckets
+                    .remove(self.placements[id.index].location, id.index);
+                self.record_fired(id.index, polled_at.unwrap_or(self.last_tick));
+                core::task::Poll::Ready(())
+            }
+        }
     }
 
-    fn compute_s_bucket_from_ms(&self, ms: u64) -> usize {
-        let secs = (ms / 1000) as usize;
-        (self.current_s_idx + secs.min(S_BUCKETS - 1)) % S_BUCKETS
+    /// polls every timer of `ids` with the same waker as [`TimeWheel::poll`] would, reading the
+    /// clock once for the whole batch. the results are appended to `out` in the order of `ids`.
+    pub fn poll_many(
+        &mut self,
+        ids: &[TimerId],
+        waker: &Waker,
+        out: &mut Vec<(TimerId, core::task::Poll<()>)>,
+    ) {
+        let now = self.clock.now();
+        out.reserve(ids.len());
+        out.extend(ids.iter().map(|&id| (id, self.poll_at(id, waker, now))));
     }
 
-    fn compute_h_bucket_from_ms(&self, ms: u64) -> usize {
-        let hours = (ms / 3_600_000) as usize;
-        (self.current_h_idx + hours.min(H_BUCKETS - 1)) % H_BUCKETS
+    /// same as [`TimeWheel::poll`] but `Ready` carries the instant the timer fired: the end of
+    /// the tick that fired it, never earlier than its deadline, or the time it was found due by
+    /// a poll or armed already due.
+    /// a released or unknown timer reports the last tick.
+    pub fn poll_fired_at(&mut self, id: TimerId, waker: &Waker) -> core::task::Poll<C::Instant> {
+        match self.poll(id, waker) {
+            core::task::Poll::Pending => core::task::Poll::Pending,
+            core::task::Poll::Ready(()) if self.storage.state(id).is_none() => {
+                core::task::Poll::Ready(self.last_tick)
+            }
+            core::task::Poll::Ready(()) => {
+                core::task::Poll::Ready(self.placements[id.index].fired_at)
+            }
+        }
     }
 
-    pub fn poll(&mut self, id: usize, waker: &Waker) -> std::task::Poll<()> {
-        self.storage.poll(id, waker)
+    /// same as [`TimeWheel::poll`] but a pending timer also reports how long until it is triggered.
+    pub fn poll_remaining(&mut self, id: TimerId, waker: &Waker) -> PollRemaining {
+        match self.storage.poll(id, waker) {
+            core::task::Poll::Ready(()) => PollRemaining::Ready,
+            core::task::Poll::Pending => {
+                PollRemaining::Pending(self.duration_until(self.placements[id.index].deadline))
+            }
+        }
+    }
+
+    /// registers a timer firing once `duration` elapsed. a zero duration timer is ready right
+    /// away: it is never woken but its first poll returns `Ready`.
+    pub fn init_timer(&mut self, duration: Duration, waker: &Waker) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        self.init_timer_with_payload(duration, waker, T::default())
     }
 
-    pub fn init_timer(
+    /// same as [`TimeWheel::init_timer`], spelled out for hot paths often hitting a limit: every
+    /// check runs before the waker is cloned, so a rejected timer allocates and clones nothing.
+    pub fn try_init_timer(
         &mut self,
         duration: Duration,
         waker: &Waker,
-    ) -> Result<usize, DurationTooLong> {
-        let total_ms = duration.as_millis() as u64;
-        if total_ms >= MAX_DURATION_HOURS * 3_600_000 {
-            return Err(DurationTooLong);
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        self.init_timer(duration, waker)
+    }
+
+    pub fn init_timer_with_payload(
+        &mut self,
+        duration: Duration,
+        waker: &Waker,
+        payload: T,
+    ) -> Result<TimerId, TimerError> {
+        // validated first, nothing is cloned or stored for a rejected timer
+        let total_ms = Self::checked_ms(duration)?;
+        self.check_capacity(1)?;
+        let now = self.clock.now();
+        let target = C::add(now, duration);
+        Ok(self.insert(self.deadline_from_ms(total_ms), target, now, waker, payload))
+    }
+
+    /// same as [`TimeWheel::init_timer`] with the duration given in ticks of the wheel, for clocks
+    /// such as [`TickClock`] where time is counted in ticks.
+    pub fn init_timer_ticks(&mut self, ticks: u64, waker: &Waker) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let duration = Duration::from_millis(ticks.saturating_mul(self.tick_ms));
+        self.init_timer(duration, waker)
+    }
+
+    /// same as [`TimeWheel::init_timer`] but among the timers firing on the same tick, the ones
+    /// with a higher priority are woken first. timers of equal priority fire in the order they
+    /// were armed, timers armed without a priority have the lowest one.
+    pub fn init_timer_with_priority(
+        &mut self,
+        duration: Duration,
+        priority: u8,
+        waker: &Waker,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let timer_id = self.init_timer(duration, waker)?;
+        self.storage.set_priority(timer_id.index, priority);
+        Ok(timer_id)
+    }
+
+    /// registers a timer firing anywhere between `earliest` and `latest`, joining the tick of a
+    /// timer already due in that window so fewer ticks have work to do. it fires at `latest`
+    /// when no timer is, and never before `earliest`. `latest` is raised to `earliest` if lower.
+    pub fn init_timer_slack(
+        &mut self,
+        earliest: Duration,
+        latest: Duration,
+        waker: &Waker,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let latest = latest.max(earliest);
+        let latest_ms = Self::checked_ms(latest)?;
+        self.check_capacity(1)?;
+        let first = self.deadline_from_ms(earliest.as_millis() as u64);
+        let last = self.deadline_from_ms(latest_ms);
+        let deadline = self.shared_deadline(first, last).unwrap_or(last);
+        let now = self.clock.now();
+        let target = C::add(now, earliest);
+        Ok(self.insert(deadline, target, now, waker, T::default()))
+    }
+
+    /// earliest deadline of a waiting timer within `first..=last`.
+    fn shared_deadline(&self, first: u64, last: u64) -> Option<u64> {
+        // the ms level holds one deadline per bucket, up to the end of the current second bucket
+        let ms_end = (self.ticks / S_SPAN + 1) * S_SPAN;
+        if let Some(deadline) = (first.max(self.ticks)..=last.min(ms_end - 1))
+            .find(|&d| self.buckets.ms_occupied.is_set((d % S_SPAN) as usize))
+        {
+            return Some(deadline);
         }
+        // coarser buckets are searched for a deadline in the window, in time order
+        let in_window = |bucket: &Bucket<N>| {
+            bucket
+                .iter()
+                .map(|&slot| self.placements[slot].deadline)
+                .filter(|deadline| (first..=last).contains(deadline))
+                .min()
+        };
+        (self.current_s_idx + 1..S_BUCKETS)
+            .filter(|&idx| self.buckets.s_occupied.is_set(idx))
+            .find_map(|idx| in_window(&self.buckets.s_level[idx]))
+            .or_else(|| {
+                (self.current_m_idx + 1..M_BUCKETS)
+                    .filter(|&idx| self.buckets.m_occupied.is_set(idx))
+                    .find_map(|idx| in_window(&self.buckets.m_level[idx]))
+            })
+            .or_else(|| {
+                (0..H_BUCKETS)
+                    .filter(|&idx| self.buckets.h_occupied.is_set(idx))
+                    .filter_map(|idx| in_window(&self.buckets.h_level[idx]))
+                    .min()
+            })
+    }
 
-        let timer_id = self.storage.create(waker);
+    /// registers a timer running `f` when it fires instead of waking a task, for event loops
+    /// outside of futures. `f` runs during the tick firing it and is dropped without running
+    /// if the timer is cancelled.
+    pub fn init_callback(
+        &mut self,
+        duration: Duration,
+        f: Box<dyn FnOnce() + Send>,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let total_ms = Self::checked_ms(duration)?;
+        self.check_capacity(1)?;
+        let deadline = self.deadline_from_ms(total_ms);
+        let target = C::add(self.clock.now(), duration);
+        let timer_id = self.storage.create_callback(f, T::default());
+        self.place(timer_id.index, deadline, target);
+        Ok(timer_id)
+    }
 
-        let ms_threshold = (MS_BUCKETS as u64) * MS_TICK;
-        let s_threshold = (S_BUCKETS as u64) * 1000;
+    /// registers a timer waking every task subscribed to it with [`TimeWheel::subscribe`] once
+    /// `duration` elapsed, all of them on the same tick. a zero duration timer is ready right away.
+    pub fn init_broadcast(&mut self, duration: Duration) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let total_ms = Self::checked_ms(duration)?;
+        self.check_capacity(1)?;
+        let now = self.clock.now();
+        let target = C::add(now, duration);
+        if target <= now {
+            let timer_id = self.storage.create_done(T::default());
+            self.record_fired(timer_id.index, now);
+            return Ok(timer_id);
+        }
+        let timer_id = self.storage.create_broadcast(T::default());
+        self.place(timer_id.index, self.deadline_from_ms(total_ms), target);
+        Ok(timer_id)
+    }
 
-        if total_ms < ms_threshold {
-            let idx = self.compute_ms_bucket_from_ms(total_ms);
-            self.buckets.ms_occupied.set(idx);
-            self.buckets.ms_level[idx].push(timer_id);
-        } else if total_ms < s_threshold {
-            let idx = self.compute_s_bucket_from_ms(total_ms);
-            self.buckets.s_occupied.set(idx);
-            self.buckets.s_level[idx].push(timer_id);
-        } else {
-            let idx = self.compute_h_bucket_from_ms(total_ms);
-            self.buckets.h_occupied.set(idx);
-            self.buckets.h_level[idx].push(timer_id);
+    /// adds the task of `waker` to the ones woken by a broadcast timer, a task is only added once.
+    /// returns `Ready` if the timer fired already. polling a broadcast timer subscribes as well.
+    pub fn subscribe(&mut self, id: TimerId, waker: &Waker) -> core::task::Poll<()> {
+        self.storage.poll(id, waker)
+    }
+
+    /// removes the task of `waker` from a broadcast timer that did not fire yet, returns false if
+    /// it was not subscribed.
+    pub fn unsubscribe(&mut self, id: TimerId, waker: &Waker) -> bool {
+        self.storage.unsubscribe(id, waker)
+    }
+
+    /// registers a timer firing every `period`, starting one period from now, until it is cancelled.
+    /// the period is rounded to the wheel's tick but is at least one tick. each fire wakes the
+    /// waker and makes one [`TimeWheel::poll`] return `Ready`, fires missed between polls are kept.
+    pub fn init_interval(&mut self, period: Duration, waker: &Waker) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        self.init_interval_jittered(period, Duration::ZERO, waker)
+    }
+
+    /// same as [`TimeWheel::init_interval`] but every fire, the first one included, is moved by
+    /// a pseudo random offset within `[-jitter, +jitter]` so intervals sharing a period spread
+    /// over several buckets. the offsets are deterministic, drawn from a small generator seeded
+    /// per timer. fires stay at least one tick apart and `period + jitter` must be under the
+    /// 24h limit.
+    pub fn init_interval_jittered(
+        &mut self,
+        period: Duration,
+        jitter: Duration,
+        waker: &Waker,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        self.init_periodic(period, jitter, None, waker)
+    }
+
+    /// same as [`TimeWheel::init_interval`] but the timer stops after firing `count` times, its
+    /// last fire completes it like a one shot timer. a zero count is ready right away.
+    pub fn init_repeating(
+        &mut self,
+        period: Duration,
+        count: usize,
+        waker: &Waker,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        if count == 0 {
+            Self::checked_ms(period)?;
+            self.check_capacity(1)?;
+            let timer_id = self.storage.create_done(T::default());
+            self.record_fired(timer_id.index, self.clock.now());
+            return Ok(timer_id);
         }
+        self.init_periodic(period, Duration::ZERO, Some(count), waker)
+    }
 
+    fn init_periodic(
+        &mut self,
+        period: Duration,
+        jitter: Duration,
+        count: Option<usize>,
+        waker: &Waker,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        Self::checked_ms(period.saturating_add(jitter))?;
+        let jitter = self.ticks_from_ms(Self::checked_ms(jitter)?);
+        let period = self.ticks_from_ms(Self::checked_ms(period)?).max(1);
+        self.check_capacity(1)?;
+        let timer_id = self
+            .storage
+            .create_periodic(waker, period, jitter, count, T::default());
+        let step = self.storage.next_step(timer_id.index).unwrap_or(period);
+        let target = C::add(self.clock.now(), Duration::from_millis(step * self.tick_ms));
+        self.place(timer_id.index, self.ticks + step, target);
         Ok(timer_id)
     }
 
-    pub fn drop(&mut self, id: usize) {
-        self.storage.drop(id);
+    /// arms a fired timer again for `duration`, reusing its slot and keeping its id. the slot
+    /// must not have been released with [`TimeWheel::drop`], and a payload not taken yet is kept.
+    pub fn rearm(
+        &mut self,
+        id: TimerId,
+        duration: Duration,
+        waker: &Waker,
+    ) -> Result<(), TimerError>
+    where
+        T: Default,
+    {
+        let total_ms = Self::checked_ms(duration)?;
+        if self.max_timers.is_some_and(|max| self.len() >= max) {
+            return Err(TimerError::TooManyTimers);
+        }
+        let now = self.clock.now();
+        let target = C::add(now, duration);
+        // a timer due right away stays done, see init_timer
+        self.storage.rearm(id, waker, target <= now)?;
+        if target > now {
+            self.place(id.index, self.deadline_from_ms(total_ms), target);
+        } else {
+            self.record_fired(id.index, now);
+        }
+        Ok(())
+    }
+
+    /// registers a timer firing at `deadline`, measured from the wheel's last tick so no time
+    /// passes between computing the duration and arming it. a deadline already reached is ready
+    /// right away, see [`TimeWheel::init_timer`].
+    pub fn init_at(&mut self, deadline: C::Instant, waker: &Waker) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let total_ms = Self::checked_ms(C::duration_between(self.last_tick, deadline))?;
+        self.check_capacity(1)?;
+        Ok(self.insert(
+            self.deadline_from_ms(total_ms),
+            deadline,
+            self.clock.now().max(self.last_tick),
+            waker,
+            T::default(),
+        ))
+    }
+
+    /// registers one timer per duration, either all of them or none if one can't be armed.
+    /// ids are returned in the order of `durations`.
+    pub fn init_timers(
+        &mut self,
+        durations: &[Duration],
+        waker: &Waker,
+    ) -> Result<Vec<TimerId>, TimerError>
+    where
+        T: Default,
+    {
+        let now = self.clock.now();
+        let mut deadlines = Vec::with_capacity(durations.len());
+        for &duration in durations {
+            let total_ms = Self::checked_ms(duration)?;
+            deadlines.push((self.deadline_from_ms(total_ms), C::add(now, duration)));
+        }
+        self.check_capacity(durations.len())?;
+
+        self.storage.reserve(durations.len());
+        Ok(deadlines
+            .into_iter()
+            .map(|(deadline, target)| self.insert(deadline, target, now, waker, T::default()))
+            .collect())
+    }
+
+    /// validates a duration before it is converted, huge durations must not wrap to short ones.
+    fn checked_ms(duration: Duration) -> Result<u64, DurationTooLong> {
+        let total_ms = duration.as_millis();
+        if total_ms >= u128::from(MAX_DURATION_HOURS * 3_600_000) {
+            return Err(DurationTooLong {
+                requested: duration,
+                max: Duration::from_hours(MAX_DURATION_HOURS),
+            });
+        }
+        Ok(total_ms as u64)
+    }
+
+    fn check_capacity(&self, additional: usize) -> Result<(), TimerError> {
+        match (self.max_timers, self.fixed_capacity) {
+            (Some(max), _) if self.len() + additional > max => Err(TimerError::TooManyTimers),
+            (_, Some(slots)) if self.storage.slots() + additional > slots => {
+                Err(TimerError::TooManyTimers)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn deadline_from_ms(&self, total_ms: u64) -> u64 {
+        self.ticks + self.ticks_from_ms(total_ms)
+    }
+
+    fn ticks_from_ms(&self, total_ms: u64) -> u64 {
+        match self.rounding {
+            RoundingPolicy::Floor => total_ms / self.tick_ms,
+            RoundingPolicy::Ceil => total_ms.div_ceil(self.tick_ms),
+            RoundingPolicy::Nearest => (total_ms + self.tick_ms / 2) / self.tick_ms,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        deadline: u64,
+        target: C::Instant,
+        now: C::Instant,
+        waker: &Waker,
+        payload: T,
+    ) -> TimerId {
+        if target <= now {
+            let timer_id = self.storage.create_done(payload);
+            self.record_fired(timer_id.index, now);
+            return timer_id;
+        }
+        let timer_id = self.storage.create(waker, payload);
+        self.place(timer_id.index, deadline, target);
+        timer_id
+    }
+
+    fn place(&mut self, slot: usize, deadline: u64, target: C::Instant) {
+        let placement = Placement {
+            location: self.location_for(deadline),
+            deadline,
+            target,
+            fired_at: self.placements.get(slot).map_or(target, |p| p.fired_at),
+        };
+        self.buckets.push(placement.location, slot);
+        if slot >= self.placements.len() {
+            self.placements.resize(slot + 1, placement);
+        }
+        self.placements[slot] = placement;
+    }
+
+    /// records the instant a timer fired, for timers done without going through a bucket as well.
+    fn record_fired(&mut self, slot: usize, at: C::Instant) {
+        if slot >= self.placements.len() {
+            let placement = Placement {
+                location: self.location_for(self.ticks),
+                deadline: self.ticks,
+                target: at,
+                fired_at: at,
+            };
+            self.placements.resize(slot + 1, placement);
+        }
+        self.placements[slot].fired_at = at;
+    }
+
+    /// takes the payload of a fired timer, returns None while the timer is waiting or if it was already taken.
+    pub fn take_payload(&mut self, id: TimerId) -> Option<T> {
+        self.storage.take_payload(id)
+    }
+
+    /// cancels the timer, a waiting timer is removed from its bucket right away and its id released.
+    /// tells whether the timer was cancelled or had fired already.
+    pub fn drop(&mut self, id: TimerId) -> DropOutcome {
+        let outcome = self.storage.drop(id);
+        if outcome == DropOutcome::Cancelled {
+            self.buckets
+                .remove(self.placements[id.index].location, id.index);
+        }
+        outcome
+    }
+
+    /// fires a waiting timer right away as its tick would, returns false if it was not waiting.
+    /// unlike [`TimeWheel::drop`] its task is woken and its next poll returns `Ready`, an
+    /// interval timer is re-armed one period from now.
+    pub fn expire_now(&mut self, id: TimerId) -> bool {
+        if !self.storage.is_pending(id) {
+            return false;
+        }
+        self.buckets
+            .remove(self.placements[id.index].location, id.index);
+        let Some((wakeup, outcome)) = self.storage.wake(id.index) else {
+            return false;
+        };
+        let now = self.clock.now();
+        self.record_fired(id.index, now);
+        if let WakeOutcome::Reschedule(step) = outcome {
+            let target = C::add(now, Duration::from_millis(step * self.tick_ms));
+            self.place(id.index, self.ticks + step, target);
+        }
+        wakeup.wake();
+        true
+    }
+
+    /// cancels every waiting timer whose payload matches `pred`, returns how many were cancelled.
+    pub fn cancel_where(&mut self, pred: impl Fn(&T) -> bool) -> usize {
+        let mut cancelled = 0;
+        let storage = &mut self.storage;
+        self.buckets.retain(|slot| {
+            if !storage.payload(slot).is_some_and(&pred) {
+                return true;
+            }
+            storage.drop(storage.id(slot));
+            cancelled += 1;
+            false
+        });
+        cancelled
+    }
+
+    /// cancels every timer without waking it and returns the ids of the ones that were waiting.
+    /// fired timers are released too, the wheel is left as new apart from its last tick instant.
+    pub fn drain_all(&mut self) -> Vec<TimerId> {
+        let mut ids = Vec::with_capacity(self.len());
+        let buckets = core::mem::replace(&mut self.buckets, BucketLevels::new());
+        for bucket in buckets
+            .ms_level
+            .into_iter()
+            .chain(buckets.s_level)
+            .chain(buckets.m_level)
+            .chain(buckets.h_level)
+        {
+            ids.extend(bucket.into_iter().map(|slot| self.storage.id(slot)));
+        }
+
+        self.storage.clear();
+        self.placements.clear();
+        self.ticks = 0;
+        self.current_ms_idx = 0;
+        self.current_s_idx = 0;
+        self.current_m_idx = 0;
+        self.current_h_idx = 0;
+        self.paused = false;
+        self.fired_last_tick = false;
+
+        ids
+    }
+
+    /// cancels every timer without waking it and resets the wheel as if it was just created,
+    /// starting from the current time. allocations are kept so the wheel can be reused.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.storage.clear();
+        self.placements.clear();
+        self.ticks = 0;
+        self.current_ms_idx = 0;
+        self.current_s_idx = 0;
+        self.current_m_idx = 0;
+        self.current_h_idx = 0;
+        self.paused = false;
+        self.fired_last_tick = false;
+        self.last_tick = self.clock.now();
+    }
+
+    /// sets the tick count and the hands pointing at it, the buckets are left as they are.
+    fn move_hands(&mut self, ticks: u64) {
+        self.ticks = ticks;
+        self.current_ms_idx = (ticks % S_SPAN) as usize;
+        self.current_s_idx = ((ticks / S_SPAN) % S_BUCKETS as u64) as usize;
+        self.current_m_idx = ((ticks / M_SPAN) % M_BUCKETS as u64) as usize;
+        self.current_h_idx = ((ticks / H_SPAN) % H_BUCKETS as u64) as usize;
+    }
+
+    /// moves every waiting timer of `other` to this wheel, keeping their wakers, callbacks and
+    /// payloads. each timer keeps the time it had left on `other`, measured from its hands.
+    /// timers get new ids, the `(old id, new id)` pairs are returned. fired timers of `other`
+    /// are discarded, nothing is moved if the timers don't fit. this is O(number of timers).
+    pub fn absorb(&mut self, mut other: Self) -> Result<Vec<(TimerId, TimerId)>, TimerError> {
+        self.check_capacity(other.len())?;
+
+        let now = self.clock.now();
+        let slots: Vec<usize> = other.buckets.iter().collect();
+        let mut remapped = Vec::with_capacity(slots.len());
+        for slot in slots {
+            let old_id = other.storage.id(slot);
+            let remaining_ms =
+                other.placements[slot].deadline.saturating_sub(other.ticks) * other.tick_ms;
+            let Some(new_id) =
+                self.storage
+                    .adopt(&mut other.storage, slot, other.tick_ms, self.tick_ms)
+            else {
+                continue;
+            };
+            let target = C::add(now, Duration::from_millis(remaining_ms));
+            self.place(new_id.index, self.deadline_from_ms(remaining_ms), target);
+            remapped.push((old_id, new_id));
+        }
+        Ok(remapped)
+    }
+
+    /// storage backend holding the timers.
+    #[inline]
+    pub fn storage(&self) -> &S {
+        self.storage.store()
+    }
+
+    /// takes the wheel apart into its clock and storage backend. every timer is cancelled first
+    /// without being woken, so the backend comes back empty and can be handed to
+    /// [`TimeWheel::from_parts`] again.
+    pub fn into_parts(mut self) -> (C, S) {
+        self.clear();
+        (self.clock, self.storage.into_store())
+    }
+
+    /// instant the wheel was last advanced to, the time elapsed since is processed by the next tick.
+    #[inline]
+    pub fn last_tick(&self) -> C::Instant {
+        self.last_tick
+    }
+
+    /// rebases the wheel on `last_tick`, for instance to align it with an external timeline before
+    /// arming anything. nothing fires and the hands don't move, the time between the old and new
+    /// instant is simply never processed. timers already waiting keep their ticks, so they shift
+    /// with the new reference, use with care once timers are armed.
+    pub fn set_last_tick(&mut self, last_tick: C::Instant) {
+        self.last_tick = last_tick;
+    }
+
+    /// captures the hands and the time of the last tick, to rewind the wheel later on with
+    /// [`TimeWheel::restore_position`].
+    pub fn position(&self) -> WheelPosition<C::Instant> {
+        WheelPosition {
+            ticks: self.ticks,
+            last_tick: self.last_tick,
+        }
+    }
+
+    /// moves the hands back, or forward, to a captured position. waiting timers keep their
+    /// deadlines and are placed again relative to the restored hands, the ones already past
+    /// the position fire on the next tick.
+    pub fn restore_position(&mut self, position: WheelPosition<C::Instant>) {
+        self.move_hands(position.ticks);
+        self.last_tick = position.last_tick;
+
+        let slots: Vec<usize> = self.buckets.iter().collect();
+        self.buckets.clear();
+        for slot in slots {
+            let Placement {
+                deadline, target, ..
+            } = self.placements[slot];
+            self.place(slot, deadline.max(self.ticks), target);
+        }
+    }
+
+    /// positions of the ms, second, minute and hour hands, the bucket each level processes next.
+    #[inline]
+    pub fn current_tick_indices(&self) -> (usize, usize, usize, usize) {
+        (
+            self.current_ms_idx,
+            self.current_s_idx,
+            self.current_m_idx,
+            self.current_h_idx,
+        )
+    }
+
+    /// time covered by one tick, the resolution of the wheel.
+    #[inline]
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_millis(self.tick_ms)
+    }
+
+    /// true if the last tick or advance woke at least one timer.
+    #[inline]
+    pub fn fired_last_tick(&self) -> bool {
+        self.fired_last_tick
+    }
+
+    /// exclusive upper bound of the durations the wheel accepts, longer ones fail with
+    /// [`TimerError::DurationTooLong`].
+    #[inline]
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_hours(MAX_DURATION_HOURS)
+    }
+
+    /// time covered by the whole ms level, the span of one second level bucket.
+    #[inline]
+    pub fn ms_level_span(&self) -> Duration {
+        Duration::from_millis(S_SPAN * self.tick_ms)
+    }
+
+    /// time covered by the whole second level, the span of one minute level bucket.
+    #[inline]
+    pub fn s_level_span(&self) -> Duration {
+        Duration::from_millis(M_SPAN * self.tick_ms)
+    }
+
+    /// time covered by the whole minute level, the span of one hour level bucket.
+    #[inline]
+    pub fn m_level_span(&self) -> Duration {
+        Duration::from_millis(H_SPAN * self.tick_ms)
+    }
+
+    /// returns the number of timers waiting to fire, fired and cancelled timers are not counted.
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// number of occupied timer slots, fired timers included until they are dropped. it goes
+    /// back to zero once every timer was dropped, which makes leaked ids easy to spot.
+    #[inline]
+    pub fn storage_len(&self) -> usize {
+        self.storage.slots()
+    }
+
+    /// number of fired timers whose slot was not released yet. a count that keeps growing points
+    /// at timers whose futures were lost without being dropped.
+    #[inline]
+    pub fn unclaimed_fired(&self) -> usize {
+        self.storage.slots() - self.storage.len()
+    }
+
+    /// releases the slots of every fired timer as [`TimeWheel::drop`] would, their payloads are
+    /// dropped and their ids polled afterwards are ready. returns how many were released, O(slots).
+    pub fn reap_done(&mut self) -> usize {
+        self.storage.reap_done()
+    }
+
+    /// number of timer slots allocated, fired timers keep their slot until dropped.
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    /// reserves room for `additional` more live timers, see [`TimeWheel::with_capacity`].
+    pub fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+        self.placements.reserve(additional);
+        // timers are spread over the ms level as they cascade down
+        for bucket in &mut self.buckets.ms_level {
+            bucket.reserve(additional / MS_BUCKETS);
+        }
+    }
+
+    /// releases the memory grown by past bursts of timers. slots are never moved so ids stay
+    /// valid, only the free slots past the last used one are given back. this is O(n) in the
+    /// number of slots and meant to be called at quiescent points, for instance once a startup
+    /// spike fired.
+    pub fn shrink_to_fit(&mut self) {
+        // a fixed capacity is kept, it could not be allocated again
+        if self.fixed_capacity.is_none() {
+            self.storage.shrink_to_fit();
+            // no slot lives past the storage's capacity
+            self.placements.truncate(self.storage.capacity());
+            self.placements.shrink_to_fit();
+        }
+        self.buckets.shrink_to_fit();
+    }
+
+    /// number of occupied buckets of the ms, second, minute and hour levels, a cheaper subset of
+    /// [`TimeWheel::stats`] telling how spread in time the timers are.
+    #[inline]
+    pub fn occupied_buckets(&self) -> (usize, usize, usize, usize) {
+        let buckets = &self.buckets;
+        (
+            buckets.ms_occupied.count(),
+            buckets.s_occupied.count(),
+            buckets.m_occupied.count(),
+            buckets.h_occupied.count(),
+        )
+    }
+
+    pub fn stats(&self) -> TimeWheelStats {
+        let buckets = &self.buckets;
+        let (occupied_ms_buckets, occupied_s_buckets, occupied_m_buckets, occupied_h_buckets) =
+            self.occupied_buckets();
+        TimeWheelStats {
+            ms_timers: buckets.ms_level.iter().map(|b| b.len()).sum(),
+            s_timers: buckets.s_level.iter().map(|b| b.len()).sum(),
+            m_timers: buckets.m_level.iter().map(|b| b.len()).sum(),
+            h_timers: buckets.h_level.iter().map(|b| b.len()).sum(),
+            occupied_ms_buckets,
+            occupied_s_buckets,
+            occupied_m_buckets,
+            occupied_h_buckets,
+        }
     }
 
     /// returns the duration until the next timer is triggered, or None if no timers are registered.
+    /// zero duration timers are ready when armed and never count as registered.
     pub fn next_deadline(&self) -> Option<Duration> {
-        for i in 0..MS_BUCKETS {
-            let idx = (self.current_ms_idx + i) % MS_BUCKETS;
+        self.peek_next().map(|(_, duration)| duration)
+    }
+
+    /// same as [`TimeWheel::next_deadline`] but as an instant of the wheel's clock, measured from
+    /// the last tick like every deadline of the wheel.
+    pub fn next_deadline_instant(&self) -> Option<C::Instant> {
+        self.next_deadline()
+            .map(|duration| C::add(self.last_tick, duration))
+    }
+
+    /// returns the soonest timer along with the duration until it is triggered,
+    /// which one is returned among the timers sharing the soonest bucket is unspecified.
+    pub fn peek_next(&self) -> Option<(TimerId, Duration)> {
+        let (slot, deadline) = self.next_timer()?;
+        Some((self.storage.id(slot), self.duration_until(deadline)))
+    }
+
+    /// true while the wheel still holds the timer, pending or fired and not dropped yet.
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.state(id).is_some()
+    }
+
+    /// state of the timer without the side effects of [`TimeWheel::poll`], None once it was
+    /// cancelled or dropped after firing since its slot is released right away.
+    pub fn state(&self, id: TimerId) -> Option<TimerState> {
+        self.storage.state(id)
+    }
+
+    /// same as [`TimeWheel::remaining`] but as an instant of the wheel's clock, measured from the
+    /// last tick. the instant is rounded to the wheel's tick like the timer's placement.
+    pub fn deadline_of(&self, id: TimerId) -> Option<C::Instant> {
+        self.remaining(id)
+            .map(|remaining| C::add(self.last_tick, remaining))
+    }
+
+    /// returns the duration until the timer is triggered, or None if it fired or was cancelled.
+    pub fn remaining(&self, id: TimerId) -> Option<Duration> {
+        if !self.storage.is_pending(id) {
+            return None;
+        }
+        Some(self.duration_until(self.placements[id.index].deadline))
+    }
+
+    /// iterates over the waiting timers with the duration until each is triggered, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (TimerId, Duration)> + '_ {
+        self.buckets.iter().map(|slot| {
+            let deadline = self.placements[slot].deadline;
+            (self.storage.id(slot), self.duration_until(deadline))
+        })
+    }
+
+    /// calls `f` with every waiting timer and the duration until it is triggered, as
+    /// [`TimeWheel::iter`] yields them.
+    pub fn for_each(&self, mut f: impl FnMut(TimerId, Duration)) {
+        self.iter().for_each(|(id, remaining)| f(id, remaining));
+    }
+
+    fn duration_until(&self, deadline: u64) -> Duration {
+        // the current bucket is drained by the next tick
+        let ticks_away = (deadline - self.ticks).max(1);
+        Duration::from_millis(ticks_away * self.tick_ms)
+    }
+
+    /// durations until the soonest timer of the ms, second, minute and hour levels, each level
+    /// on its own. [`TimeWheel::next_deadline`] is the first of them that is set.
+    pub fn next_deadline_per_level(
+        &self,
+    ) -> (
+        Option<Duration>,
+        Option<Duration>,
+        Option<Duration>,
+        Option<Duration>,
+    ) {
+        let until = |timer: Option<(usize, u64)>| timer.map(|(_, d)| self.duration_until(d));
+        (
+            until(self.next_ms_timer()),
+            until(self.next_s_timer()),
+            until(self.next_m_timer()),
+            until(self.next_h_timer()),
+        )
+    }
+
+    fn next_timer(&self) -> Option<(usize, u64)> {
+        // every level only holds deadlines later than the ones of the level below
+        self.next_ms_timer()
+            .or_else(|| self.next_s_timer())
+            .or_else(|| self.next_m_timer())
+            .or_else(|| self.next_h_timer())
+    }
+
+    fn next_ms_timer(&self) -> Option<(usize, u64)> {
+        // buckets behind the hands are always empty
+        (self.current_ms_idx..MS_BUCKETS)
+            .find(|&idx| self.buckets.ms_occupied.is_set(idx))
+            .map(|idx| {
+                let deadline = self.ticks + (idx - self.current_ms_idx) as u64;
+                (self.buckets.ms_level[idx][0], deadline)
+            })
+    }
+
+    fn next_s_timer(&self) -> Option<(usize, u64)> {
+        (self.current_s_idx + 1..S_BUCKETS)
+            .find(|&idx| self.buckets.s_occupied.is_set(idx))
+            .and_then(|idx| self.earliest_timer(&self.buckets.s_level[idx]))
+    }
+
+    fn next_m_timer(&self) -> Option<(usize, u64)> {
+        (self.current_m_idx + 1..M_BUCKETS)
+            .find(|&idx| self.buckets.m_occupied.is_set(idx))
+            .and_then(|idx| self.earliest_timer(&self.buckets.m_level[idx]))
+    }
+
+    fn next_h_timer(&self) -> Option<(usize, u64)> {
+        // a bucket may hold later rotations before an earlier one in hand order, all are checked
+        (0..H_BUCKETS)
+            .filter(|&idx| self.buckets.h_occupied.is_set(idx))
+            .filter_map(|idx| self.earliest_timer(&self.buckets.h_level[idx]))
+            .min_by_key(|&(_, deadline)| deadline)
+    }
+
+    /// first tick from `ticks` on whose processing fires or cascades something, u64::MAX if
+    /// the wheel is empty. a boundary cascades when the tick before it is processed.
+    fn next_busy_tick(&self) -> u64 {
+        // buckets behind the hands are empty, see next_timer
+        for idx in self.current_ms_idx..MS_BUCKETS {
             if self.buckets.ms_occupied.is_set(idx) {
-                let ticks_away = if i == 0 { None } else { Some(i) };
-                return ticks_away.map(|v| Duration::from_millis(v as u64 * MS_TICK));
+                return self.ticks + (idx - self.current_ms_idx) as u64;
             }
         }
 
-        for i in 0..S_BUCKETS {
-            let idx = (self.current_s_idx + i) % S_BUCKETS;
+        let minute_start = self.ticks / M_SPAN * M_SPAN;
+        for idx in self.current_s_idx + 1..S_BUCKETS {
             if self.buckets.s_occupied.is_set(idx) {
-                let ms_remaining = (MS_BUCKETS - self.current_ms_idx) * MS_TICK as usize;
-                let s_remaining = i * 1000;
-                return Some(Duration::from_millis((ms_remaining + s_remaining) as u64));
+                return minute_start + idx as u64 * S_SPAN - 1;
+            }
+        }
+
+        let hour_start = self.ticks / H_SPAN * H_SPAN;
+        for idx in self.current_m_idx + 1..M_BUCKETS {
+            if self.buckets.m_occupied.is_set(idx) {
+                return hour_start + idx as u64 * M_SPAN - 1;
             }
         }
 
-        for i in 0..H_BUCKETS {
-            let idx = (self.current_h_idx + i) % H_BUCKETS;
+        // buckets up to the hand are cascaded in the next rotation
+        let rotation_start = self.ticks / WHEEL_SPAN * WHEEL_SPAN;
+        for idx in (self.current_h_idx + 1..H_BUCKETS).chain(0..=self.current_h_idx) {
             if self.buckets.h_occupied.is_set(idx) {
-                let ms_remaining = (MS_BUCKETS - self.current_ms_idx) * MS_TICK as usize;
-                let s_remaining = (S_BUCKETS - self.current_s_idx - 1) * 1000;
-                let h_remaining = i * 3600 * 1000;
-                return Some(Duration::from_millis(
-                    (ms_remaining + s_remaining + h_remaining) as u64,
-                ));
+                let rotation = if idx > self.current_h_idx {
+                    rotation_start
+                } else {
+                    rotation_start + WHEEL_SPAN
+                };
+                return rotation + idx as u64 * H_SPAN - 1;
             }
         }
 
-        None
+        u64::MAX
+    }
+
+    fn earliest_timer(&self, bucket: &Bucket<N>) -> Option<(usize, u64)> {
+        bucket
+            .iter()
+            .map(|&id| (id, self.placements[id].deadline))
+            .min_by_key(|&(_, deadline)| deadline)
     }
 }
 
@@ -270,3 +2014,56 @@ impl Default for TimeWheel {
         Self::new()
     }
 }
+
+/// occupied buckets of each level with the number of timers they hold, e.g. `ms[2:3, 5:1] s[10:2] m[] h[]`.
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> fmt::Display for TimeWheel<T, C, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.buckets, f)
+    }
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> fmt::Debug for TimeWheel<T, C, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeWheel")
+            .field("ticks", &self.ticks)
+            .field("hands", &self.current_tick_indices())
+            .field("len", &self.len())
+            .field("paused", &self.paused)
+            .field("buckets", &self.buckets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> fmt::Display for BucketLevels<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn level<const N: usize>(
+            f: &mut fmt::Formatter<'_>,
+            name: &str,
+            buckets: &[Bucket<N>],
+        ) -> fmt::Result {
+            write!(f, "{name}[")?;
+            let occupied = buckets.iter().enumerate().filter(|(_, b)| !b.is_empty());
+            for (i, (idx, bucket)) in occupied.enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{idx}:{}", bucket.len())?;
+            }
+            f.write_str("]")
+        }
+
+        level(f, "ms", &self.ms_level)?;
+        f.write_str(" ")?;
+        level(f, "s", &self.s_level)?;
+        f.write_str(" ")?;
+        level(f, "m", &self.m_level)?;
+        f.write_str(" ")?;
+        level(f, "h", &self.h_level)
+    }
+}
+
+impl<const N: usize> fmt::Debug for BucketLevels<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{Sleep, TimeWheel, TimerError};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+#[cfg(feature = "driver")]
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+thread_local! {
+    static WHEEL: Rc<RefCell<TimeWheel>> = Rc::new(RefCell::new(TimeWheel::new()));
+}
+
+/// wheel of the current thread used by [`sleep`], created on first use. it has to be ticked
+/// like any other wheel, by [`block_on`] with the `driver` feature or by the embedder.
+pub fn local_wheel() -> Rc<RefCell<TimeWheel>> {
+    WHEEL.with(Rc::clone)
+}
+
+/// future completing once `duration` elapsed, armed on the wheel of the current thread.
+///
+/// # Panics
+///
+/// panics if the timer can't be armed, for instance when `duration` exceeds the wheel's
+/// maximum, see [`try_sleep`] to handle the error instead.
+pub fn sleep(duration: Duration) -> Sleep {
+    try_sleep(duration).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// same as [`sleep`] but returns the error when the timer can't be armed.
+pub fn try_sleep(duration: Duration) -> Result<Sleep, TimerError> {
+    WHEEL.with(|wheel| TimeWheel::sleep(wheel, duration))
+}
+
+#[cfg(feature = "driver")]
+struct ThreadWaker(Thread);
+
+#[cfg(feature = "driver")]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// runs `future` to completion on the current thread, driving the wheel of the thread in
+/// between polls. the thread parks until the next deadline instead of ticking in a loop, and
+/// wakes early when the future is woken from elsewhere.
+#[cfg(feature = "driver")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let wheel = local_wheel();
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        let next_deadline = wheel.borrow().next_deadline();
+        match next_deadline {
+            Some(duration) => thread::park_timeout(duration),
+            None => thread::park(),
+        }
+        wheel.borrow_mut().tick();
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{Clock, DefaultClock, DropOutcome, TimeWheel, TimerError, TimerId, Wakeup};
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+/// handle to a wheel shared between threads, typically ticked by the thread owning it while
+/// other threads arm and cancel timers.
+///
+/// the handle is `Send` and `Sync` as long as the clock and its instants are `Send`, which holds
+/// for the default [`StdClock`](crate::StdClock) but not for a [`MockClock`](crate::MockClock)
+/// whose clones share an `Rc`. clones refer to the same wheel. every method locks the wheel for
+/// its duration, but wakers are always called after the lock is released so a waker may use the
+/// handle again without deadlocking.
+pub struct SharedTimeWheel<C: Clock = DefaultClock> {
+    inner: Arc<Mutex<TimeWheel<(), C>>>,
+}
+
+impl SharedTimeWheel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C: Clock> SharedTimeWheel<C> {
+    /// creates a shared wheel reading the time from `clock`.
+    #[must_use]
+    pub fn new_with_clock(clock: C) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TimeWheel::new_with_clock(clock))),
+        }
+    }
+
+    pub fn arm(&self, duration: Duration, waker: &Waker) -> Result<TimerId, TimerError> {
+        self.lock().init_timer(duration, waker)
+    }
+
+    /// same as [`SharedTimeWheel::arm`] but the timer is cancelled when the returned handle is dropped.
+    pub fn arm_handle(
+        &self,
+        duration: Duration,
+        waker: &Waker,
+    ) -> Result<TimerHandle<C>, TimerError> {
+        Ok(TimerHandle {
+            wheel: self.clone(),
+            id: self.arm(duration, waker)?,
+        })
+    }
+
+    pub fn cancel(&self, id: TimerId) -> DropOutcome {
+        TimeWheel::drop(&mut self.lock(), id)
+    }
+
+    pub fn poll(&self, id: TimerId, waker: &Waker) -> Poll<()> {
+        self.lock().poll(id, waker)
+    }
+
+    /// fires the timers due since the last tick, returns how many were woken.
+    pub fn tick(&self) -> usize {
+        let wakeups = {
+            let mut wheel = self.lock();
+            let now = wheel.clock.now();
+            wheel.advance_deferred(now)
+        };
+        Self::wake_all(wakeups)
+    }
+
+    /// same as [`SharedTimeWheel::tick`] up to `now`, see [`TimeWheel::advance_to`].
+    pub fn advance_to(&self, now: C::Instant) -> usize {
+        let wakeups = self.lock().advance_deferred(now);
+        Self::wake_all(wakeups)
+    }
+
+    fn wake_all(wakeups: Vec<Wakeup>) -> usize {
+        let fired = wakeups.len();
+        for wakeup in wakeups {
+            wakeup.wake();
+        }
+        fired
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimeWheel<(), C>> {
+        // wakers never run under the lock, a poisoned wheel is still consistent
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// derived impls would require `C: Clone` and a `Default` wheel for every clock
+impl<C: Clock> Clone for SharedTimeWheel<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for SharedTimeWheel {
+    fn default() -> Self {
+        Self {
+            inner: Arc::default(),
+        }
+    }
+}
+
+/// timer armed with [`SharedTimeWheel::arm_handle`], cancelled when the handle is dropped so a
+/// future owning it can be dropped mid-flight without leaking its slot.
+/// it is `Send` and `Sync` under the same conditions as its [`SharedTimeWheel`].
+pub struct TimerHandle<C: Clock = DefaultClock> {
+    wheel: SharedTimeWheel<C>,
+    id: TimerId,
+}
+
+impl<C: Clock> TimerHandle<C> {
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    pub fn poll(&self, waker: &Waker) -> Poll<()> {
+        self.wheel.poll(self.id, waker)
+    }
+}
+
+impl<C: Clock> Drop for TimerHandle<C> {
+    fn drop(&mut self) {
+        self.wheel.cancel(self.id);
+    }
+}
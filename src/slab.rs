@@ -1,56 +1,679 @@
-use std::task::Waker;
+use crate::{DropOutcome, TimerError, TimerId, TimerState};
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use core::task::Waker;
+use smallvec::SmallVec;
 
-#[derive(Default)]
-pub struct TimerStorage {
-    inner: slab::Slab<Timer>,
+// tasks woken by a broadcast timer, most are shared by a couple of tasks
+type Subscribers = SmallVec<[Waker; 2]>;
+
+/// storage of the timers of a [`TimeWheel`](crate::TimeWheel), a slab of [`Timer`]s indexed by
+/// slot. the store only holds the timers, the wheel decides when they fire and what they become.
+/// [`TimerStorage`] is the default, backed by a slab with a generation per slot.
+///
+/// a slot is occupied from the insertion of a timer until it is removed, and ids of a released
+/// slot must not match any timer anymore. slot indices must stay dense: the wheel keeps a
+/// vector indexed by slot next to the store, so every occupied slot has an index below
+/// [`TimerStore::capacity`], shrinking included, and released slots are reused rather than
+/// handing out ever growing indices.
+pub trait TimerStore<T>: Default {
+    /// stores a timer in a free slot and returns its id, whose index is below the capacity.
+    fn insert(&mut self, timer: Timer<T>) -> TimerId;
+
+    /// releases the slot of `id` and hands back its timer, None if the id is stale.
+    fn remove(&mut self, id: TimerId) -> Option<Timer<T>>;
+
+    fn get(&self, id: TimerId) -> Option<&Timer<T>>;
+
+    fn get_mut(&mut self, id: TimerId) -> Option<&mut Timer<T>>;
+
+    /// id of the timer occupying the slot, the slot must be occupied. indices are dense, see
+    /// the trait documentation.
+    fn id(&self, index: usize) -> TimerId;
+
+    /// releases the slot of every timer for which `keep` returns false.
+    fn retain(&mut self, keep: impl FnMut(&mut Timer<T>) -> bool);
+
+    /// releases every slot, ids handed out so far become stale.
+    fn clear(&mut self);
+
+    /// number of occupied slots.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn reserve(&mut self, additional: usize);
+
+    fn capacity(&self) -> usize;
+
+    /// releases unused memory, the capacity stays above the index of every occupied slot.
+    fn shrink_to_fit(&mut self);
+}
+
+/// a timer as kept by a [`TimerStore`], opaque to the store.
+pub struct Timer<T> {
+    kind: TimerKind<T>,
+    // timers with a higher priority fire first among the ones sharing a tick
+    priority: u8,
+}
+
+enum TimerKind<T> {
+    Waiting(Waker, T),
+    // runs the closure instead of waking a task
+    Callback(Box<dyn FnOnce() + Send>, T),
+    // wakes every subscribed task at once
+    Broadcast(Subscribers, T),
+    // re-armed by the wheel following its schedule, `fired` counts the fires not yet polled.
+    // `remaining` counts the fires left before it is done, None repeats until cancelled
+    Periodic {
+        waker: Waker,
+        schedule: Schedule,
+        fired: usize,
+        remaining: Option<usize>,
+        payload: T,
+    },
+    // the payload is kept until taken or the slot is released
+    Done(Option<T>),
+}
+
+impl<T: Clone> Clone for Timer<T> {
+    fn clone(&self) -> Self {
+        let kind = match &self.kind {
+            TimerKind::Waiting(waker, payload) => {
+                TimerKind::Waiting(waker.clone(), payload.clone())
+            }
+            // a closure runs once, the copy only keeps the schedule
+            TimerKind::Callback(_, payload) => {
+                TimerKind::Callback(Box::new(|| {}), payload.clone())
+            }
+            TimerKind::Broadcast(wakers, payload) => {
+                TimerKind::Broadcast(wakers.clone(), payload.clone())
+            }
+            TimerKind::Periodic {
+                waker,
+                schedule,
+                fired,
+                remaining,
+                payload,
+            } => TimerKind::Periodic {
+                waker: waker.clone(),
+                schedule: schedule.clone(),
+                fired: *fired,
+                remaining: *remaining,
+                payload: payload.clone(),
+            },
+            TimerKind::Done(payload) => TimerKind::Done(payload.clone()),
+        };
+        Self {
+            kind,
+            priority: self.priority,
+        }
+    }
+}
+
+impl<T> Timer<T> {
+    fn new(kind: TimerKind<T>) -> Self {
+        Self { kind, priority: 0 }
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self.kind, TimerKind::Done(_))
+    }
 }
 
-enum Timer {
-    Waiting(Waker),
-    Done,
-    Cancelled,
+/// spacing of the fires of a periodic timer, in ticks.
+#[derive(Clone)]
+struct Schedule {
+    period: u64,
+    // the fires are moved by up to this many ticks either way
+    jitter: u64,
+    // xorshift state, never zero
+    rng: u64,
 }
 
-impl TimerStorage {
-    pub(crate) fn create(&mut self, waker: &Waker) -> usize {
-        self.inner.insert(Timer::Waiting(waker.clone()))
+impl Schedule {
+    /// converts the schedule to ticks of `to_tick_ms` from ticks of `from_tick_ms`.
+    fn retick(&mut self, from_tick_ms: u64, to_tick_ms: u64) {
+        self.period = (self.period * from_tick_ms / to_tick_ms).max(1);
+        self.jitter = self.jitter * from_tick_ms / to_tick_ms;
+    }
+
+    /// ticks until the next fire, at least one.
+    fn next_step(&mut self) -> u64 {
+        if self.jitter == 0 {
+            return self.period;
+        }
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        let offset = self.rng % (2 * self.jitter + 1);
+        (self.period + offset).saturating_sub(self.jitter).max(1)
     }
+}
+
+/// what firing a timer triggers, handed out by [`Timers::wake`].
+pub(crate) enum Wakeup {
+    Waker(Waker),
+    Callback(Box<dyn FnOnce() + Send>),
+    Wakers(Subscribers),
+    // the task was woken already by a timer of the same bucket
+    Coalesced,
+}
 
-    pub(crate) fn drop(&mut self, id: usize) {
-        let timer = self.inner.get_mut(id).unwrap();
-        match timer {
-            Timer::Waiting(_) => {
-                *timer = Timer::Cancelled;
-                return;
+/// what becomes of a timer once fired, the timers know nothing of buckets and leave the
+/// re-arming to the wheel.
+pub(crate) enum WakeOutcome {
+    Fired,
+    // periodic timer to place again this many ticks after its deadline
+    Reschedule(u64),
+}
+
+impl Wakeup {
+    pub(crate) fn wake(self) {
+        match self {
+            Wakeup::Waker(waker) => waker.wake(),
+            Wakeup::Callback(f) => f(),
+            Wakeup::Wakers(wakers) => wakers.into_iter().for_each(Waker::wake),
+            Wakeup::Coalesced => {}
+        }
+    }
+}
+
+/// default [`TimerStore`], a slab of timers with a generation per slot so ids of released
+/// slots go stale.
+#[derive(Clone)]
+pub struct TimerStorage<T> {
+    inner: slab::Slab<Timer<T>>,
+    // generation of each slot, bumped whenever the slot is released so stale ids don't match
+    generations: Vec<u32>,
+}
+
+impl<T> Default for TimerStorage<T> {
+    fn default() -> Self {
+        Self {
+            inner: slab::Slab::new(),
+            generations: Vec::new(),
+        }
+    }
+}
+
+impl<T> TimerStore<T> for TimerStorage<T> {
+    fn insert(&mut self, timer: Timer<T>) -> TimerId {
+        let index = self.inner.insert(timer);
+        if index >= self.generations.len() {
+            self.generations.resize(index + 1, 0);
+        }
+        self.id(index)
+    }
+
+    fn remove(&mut self, id: TimerId) -> Option<Timer<T>> {
+        self.get(id)?;
+        self.generations[id.index] = id.generation.wrapping_add(1);
+        Some(self.inner.remove(id.index))
+    }
+
+    fn get(&self, id: TimerId) -> Option<&Timer<T>> {
+        if self.generations.get(id.index) != Some(&id.generation) {
+            return None;
+        }
+        self.inner.get(id.index)
+    }
+
+    fn get_mut(&mut self, id: TimerId) -> Option<&mut Timer<T>> {
+        if self.generations.get(id.index) != Some(&id.generation) {
+            return None;
+        }
+        self.inner.get_mut(id.index)
+    }
+
+    fn id(&self, index: usize) -> TimerId {
+        TimerId {
+            index,
+            generation: self.generations[index],
+        }
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&mut Timer<T>) -> bool) {
+        let generations = &mut self.generations;
+        self.inner.retain(|index, timer| {
+            let kept = keep(timer);
+            if !kept {
+                generations[index] = generations[index].wrapping_add(1);
             }
-            Timer::Done => {}
-            Timer::Cancelled => return,
+            kept
+        });
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        for generation in &mut self.generations {
+            *generation = generation.wrapping_add(1);
         }
-        self.inner.remove(id);
     }
 
-    pub(crate) fn poll(&mut self, id: usize, waker: &Waker) -> std::task::Poll<()> {
-        let timers = self.inner.get_mut(id).unwrap();
-        if let Timer::Waiting(r_waker) = timers {
-            if !r_waker.will_wake(waker) {
-                *r_waker = waker.clone();
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+        self.generations.reserve(additional);
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    // generations are kept so ids of released slots stay stale.
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+}
+
+/// timers of a wheel kept in its [`TimerStore`], with what happens to them when polled, fired
+/// or cancelled.
+#[derive(Clone)]
+pub(crate) struct Timers<T, S> {
+    store: S,
+    // number of timers still to fire, every kind but `Done`
+    active: usize,
+    // number of timers with a priority set, fired ones included until their slot is released
+    prioritized: usize,
+    payload: PhantomData<T>,
+}
+
+impl<T, S: TimerStore<T>> Timers<T, S> {
+    /// wraps an empty store.
+    pub(crate) fn from_store(store: S) -> Self {
+        Self {
+            store,
+            active: 0,
+            prioritized: 0,
+            payload: PhantomData,
+        }
+    }
+
+    pub(crate) fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub(crate) fn into_store(self) -> S {
+        self.store
+    }
+
+    fn insert(&mut self, timer: Timer<T>) -> TimerId {
+        if !timer.is_done() {
+            self.active += 1;
+        }
+        if timer.priority > 0 {
+            self.prioritized += 1;
+        }
+        let id = self.store.insert(timer);
+        debug_assert!(
+            id.index < self.store.capacity(),
+            "store handed out slot {} past its capacity",
+            id.index
+        );
+        trace!("arming timer {id:?}");
+        id
+    }
+
+    /// releases the slot and hands back its timer.
+    fn take(&mut self, id: TimerId) -> Option<Timer<T>> {
+        let timer = self.store.remove(id)?;
+        if !timer.is_done() {
+            self.active -= 1;
+        }
+        if timer.priority > 0 {
+            self.prioritized -= 1;
+        }
+        Some(timer)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut Timer<T>> {
+        let id = self.store.id(index);
+        self.store.get_mut(id)
+    }
+
+    pub(crate) fn create(&mut self, waker: &Waker, payload: T) -> TimerId {
+        self.insert(Timer::new(TimerKind::Waiting(waker.clone(), payload)))
+    }
+
+    pub(crate) fn create_callback(&mut self, f: Box<dyn FnOnce() + Send>, payload: T) -> TimerId {
+        self.insert(Timer::new(TimerKind::Callback(f, payload)))
+    }
+
+    pub(crate) fn create_broadcast(&mut self, payload: T) -> TimerId {
+        self.insert(Timer::new(TimerKind::Broadcast(
+            Subscribers::new(),
+            payload,
+        )))
+    }
+
+    /// stores a timer firing every `period` ticks, moved by up to `jitter` ticks either way.
+    /// `remaining` counts its fires, None repeats until cancelled.
+    pub(crate) fn create_periodic(
+        &mut self,
+        waker: &Waker,
+        period: u64,
+        jitter: u64,
+        remaining: Option<usize>,
+        payload: T,
+    ) -> TimerId {
+        let id = self.insert(Timer::new(TimerKind::Periodic {
+            waker: waker.clone(),
+            schedule: Schedule {
+                period,
+                jitter,
+                rng: 1,
+            },
+            fired: 0,
+            remaining,
+            payload,
+        }));
+        // seeded from the slot and its generation, so timers armed together draw different offsets
+        if let Some(Timer {
+            kind: TimerKind::Periodic { schedule, .. },
+            ..
+        }) = self.store.get_mut(id)
+        {
+            let seed = ((id.index as u64) << 32 | id.generation as u64).wrapping_add(1);
+            schedule.rng = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        }
+        id
+    }
+
+    /// stores a timer that is due already, it is ready without going through the wheel.
+    pub(crate) fn create_done(&mut self, payload: T) -> TimerId {
+        self.insert(Timer::new(TimerKind::Done(Some(payload))))
+    }
+
+    /// moves the timer of `index` out of `other`, whose ticks last `from_tick_ms`, releasing
+    /// its slot there. returns None if the slot was free.
+    pub(crate) fn adopt(
+        &mut self,
+        other: &mut Self,
+        index: usize,
+        from_tick_ms: u64,
+        to_tick_ms: u64,
+    ) -> Option<TimerId> {
+        let mut timer = other.take(other.store.id(index))?;
+        if let TimerKind::Periodic { schedule, .. } = &mut timer.kind {
+            schedule.retick(from_tick_ms, to_tick_ms);
+        }
+        Some(self.insert(timer))
+    }
+
+    /// turns a done timer back into a waiting one, unless `ready` where it is left done.
+    pub(crate) fn rearm(
+        &mut self,
+        id: TimerId,
+        waker: &Waker,
+        ready: bool,
+    ) -> Result<(), TimerError>
+    where
+        T: Default,
+    {
+        let timer = self.store.get_mut(id).ok_or(TimerError::UnknownTimer)?;
+        let TimerKind::Done(payload) = &mut timer.kind else {
+            return Err(TimerError::StillPending);
+        };
+        if !ready {
+            let payload = payload.take().unwrap_or_default();
+            timer.kind = TimerKind::Waiting(waker.clone(), payload);
+            self.active += 1;
+            trace!("rearming timer {id:?}");
+        }
+        Ok(())
+    }
+
+    /// id of the timer currently occupying the slot.
+    pub(crate) fn id(&self, index: usize) -> TimerId {
+        self.store.id(index)
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.store.reserve(additional);
+    }
+
+    pub(crate) fn priority(&self, index: usize) -> u8 {
+        self.store
+            .get(self.store.id(index))
+            .map_or(0, |timer| timer.priority)
+    }
+
+    pub(crate) fn set_priority(&mut self, index: usize, priority: u8) {
+        let Some(timer) = self.get_mut(index) else {
+            return;
+        };
+        let was_set = timer.priority > 0;
+        timer.priority = priority;
+        match (was_set, priority > 0) {
+            (false, true) => self.prioritized += 1,
+            (true, false) => self.prioritized -= 1,
+            _ => {}
+        }
+    }
+
+    /// true if any timer has a priority set, buckets only need sorting then.
+    pub(crate) fn has_priorities(&self) -> bool {
+        self.prioritized > 0
+    }
+
+    /// occupied slots, fired timers included until they are dropped.
+    pub(crate) fn slots(&self) -> usize {
+        self.store.len()
+    }
+
+    /// releases the slots of every fired timer, returns how many there were.
+    pub(crate) fn reap_done(&mut self) -> usize {
+        let mut reaped = 0;
+        let prioritized = &mut self.prioritized;
+        self.store.retain(|timer| {
+            let done = timer.is_done();
+            if done {
+                reaped += 1;
+                *prioritized -= usize::from(timer.priority > 0);
+            }
+            !done
+        });
+        reaped
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.store.capacity()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.store.shrink_to_fit();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.store.clear();
+        self.active = 0;
+        self.prioritized = 0;
+    }
+
+    /// number of timers still to fire.
+    pub(crate) fn len(&self) -> usize {
+        self.active
+    }
+
+    /// releases the slot, a cancelled timer was still waiting and must be unlinked from its bucket.
+    pub(crate) fn drop(&mut self, id: TimerId) -> DropOutcome {
+        let Some(timer) = self.take(id) else {
+            return DropOutcome::Unknown;
+        };
+        trace!("dropping timer {id:?}");
+        // a cancelled callback is dropped without running
+        if timer.is_done() {
+            DropOutcome::AlreadyFired
+        } else {
+            DropOutcome::Cancelled
+        }
+    }
+
+    /// same as [`Self::poll`] but a waiting timer for which `due` holds is fired on the spot,
+    /// `due` is only called for waiting timers. returns None if the poll fired the timer.
+    pub(crate) fn poll_due(
+        &mut self,
+        id: TimerId,
+        waker: &Waker,
+        due: impl FnOnce() -> bool,
+    ) -> Option<core::task::Poll<()>> {
+        // the common case of a waiting timer is served with a single lookup
+        if let Some(Timer {
+            kind: TimerKind::Waiting(r_waker, _),
+            ..
+        }) = self.store.get_mut(id)
+        {
+            if !due() {
+                if !r_waker.will_wake(waker) {
+                    *r_waker = waker.clone();
+                }
+                return Some(core::task::Poll::Pending);
             }
-            return std::task::Poll::Pending;
+            self.wake(id.index);
+            return None;
         }
-        std::task::Poll::Ready(())
+        Some(self.poll(id, waker))
     }
 
-    pub(crate) fn wake(&mut self, id: usize) {
-        let timer = self.inner.get_mut(id).unwrap();
-        match timer {
-            Timer::Waiting(waker) => {
-                waker.wake_by_ref();
-                *timer = Timer::Done;
-                return;
+    /// ready once the timer fired or its slot was released, pending timers keep `waker`.
+    pub(crate) fn poll(&mut self, id: TimerId, waker: &Waker) -> core::task::Poll<()> {
+        trace!("polling timer {id:?}");
+        match self.store.get_mut(id).map(|timer| &mut timer.kind) {
+            Some(TimerKind::Waiting(r_waker, _)) => {
+                if !r_waker.will_wake(waker) {
+                    *r_waker = waker.clone();
+                }
+                core::task::Poll::Pending
+            }
+            // the closure is what gets notified, the waker is not kept
+            Some(TimerKind::Callback(..)) => core::task::Poll::Pending,
+            // every task polling it is subscribed
+            Some(TimerKind::Broadcast(wakers, _)) => {
+                if !wakers.iter().any(|w| w.will_wake(waker)) {
+                    wakers.push(waker.clone());
+                }
+                core::task::Poll::Pending
             }
-            Timer::Done => unreachable!(),
-            Timer::Cancelled => {}
+            // every poll consumes one fire
+            Some(TimerKind::Periodic {
+                waker: r_waker,
+                fired,
+                ..
+            }) => {
+                if !r_waker.will_wake(waker) {
+                    *r_waker = waker.clone();
+                }
+                if *fired == 0 {
+                    return core::task::Poll::Pending;
+                }
+                *fired -= 1;
+                core::task::Poll::Ready(())
+            }
+            // a released slot or a stale generation belongs to a cancelled timer
+            Some(TimerKind::Done(_)) | None => core::task::Poll::Ready(()),
         }
-        self.inner.remove(id);
+    }
+
+    /// removes the task of `waker` from a broadcast timer, returns false if it was not subscribed.
+    pub(crate) fn unsubscribe(&mut self, id: TimerId, waker: &Waker) -> bool {
+        let Some(TimerKind::Broadcast(wakers, _)) = self.store.get_mut(id).map(|t| &mut t.kind)
+        else {
+            return false;
+        };
+        let len = wakers.len();
+        wakers.retain(|w| !w.will_wake(waker));
+        wakers.len() < len
+    }
+
+    /// state of the timer, None once its slot was released.
+    pub(crate) fn state(&self, id: TimerId) -> Option<TimerState> {
+        if self.store.get(id)?.is_done() {
+            Some(TimerState::Fired)
+        } else {
+            Some(TimerState::Pending)
+        }
+    }
+
+    /// true if the timer still has to fire.
+    pub(crate) fn is_pending(&self, id: TimerId) -> bool {
+        self.store.get(id).is_some_and(|timer| !timer.is_done())
+    }
+
+    /// payload of a timer that did not fire yet.
+    pub(crate) fn payload(&self, index: usize) -> Option<&T> {
+        match &self.store.get(self.store.id(index))?.kind {
+            TimerKind::Waiting(_, payload)
+            | TimerKind::Callback(_, payload)
+            | TimerKind::Broadcast(_, payload)
+            | TimerKind::Periodic { payload, .. } => Some(payload),
+            TimerKind::Done(_) => None,
+        }
+    }
+
+    /// takes the payload of a fired timer.
+    pub(crate) fn take_payload(&mut self, id: TimerId) -> Option<T> {
+        match &mut self.store.get_mut(id)?.kind {
+            TimerKind::Done(payload) => payload.take(),
+            _ => None,
+        }
+    }
+
+    /// ticks until the first fire of a periodic timer, the next ones are given by [`Self::wake`].
+    pub(crate) fn next_step(&mut self, index: usize) -> Option<u64> {
+        match &mut self.get_mut(index)?.kind {
+            TimerKind::Periodic { schedule, .. } => Some(schedule.next_step()),
+            _ => None,
+        }
+    }
+
+    /// marks a waiting timer as done and hands back what it must notify, returns None if there
+    /// was nothing to fire. a periodic timer stays armed until its last fire and is rescheduled
+    /// by the returned step. a timer already done is left as is.
+    pub(crate) fn wake(&mut self, index: usize) -> Option<(Wakeup, WakeOutcome)> {
+        trace!("waking timer {:?}", self.id(index));
+        let timer = self.get_mut(index)?;
+        let (wakeup, payload) = match core::mem::replace(&mut timer.kind, TimerKind::Done(None)) {
+            TimerKind::Waiting(waker, payload) => (Wakeup::Waker(waker), payload),
+            TimerKind::Callback(f, payload) => (Wakeup::Callback(f), payload),
+            TimerKind::Broadcast(wakers, payload) => (Wakeup::Wakers(wakers), payload),
+            // the last fire of a repeating timer completes it
+            TimerKind::Periodic {
+                waker,
+                remaining: Some(1),
+                payload,
+                ..
+            } => (Wakeup::Waker(waker), payload),
+            TimerKind::Periodic {
+                waker,
+                mut schedule,
+                fired,
+                remaining,
+                payload,
+            } => {
+                let wakeup = Wakeup::Waker(waker.clone());
+                let step = schedule.next_step();
+                timer.kind = TimerKind::Periodic {
+                    waker,
+                    schedule,
+                    fired: fired.saturating_add(1),
+                    remaining: remaining.map(|n| n - 1),
+                    payload,
+                };
+                return Some((wakeup, WakeOutcome::Reschedule(step)));
+            }
+            done @ TimerKind::Done(_) => {
+                timer.kind = done;
+                return None;
+            }
+        };
+        timer.kind = TimerKind::Done(Some(payload));
+        self.active -= 1;
+        Some((wakeup, WakeOutcome::Fired))
     }
 }
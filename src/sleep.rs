@@ -0,0 +1,41 @@
+use crate::{Clock, DefaultClock, TimeWheel, TimerError, TimerId};
+use alloc::rc::Rc;
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// future that completes once its timer fires, the timer is cancelled when the future is dropped.
+pub struct Sleep<C: Clock = DefaultClock> {
+    wheel: Rc<RefCell<TimeWheel<(), C>>>,
+    id: TimerId,
+}
+
+impl<C: Clock> TimeWheel<(), C> {
+    /// arms a timer on the shared wheel and returns a future resolving when it fires.
+    pub fn sleep(wheel: &Rc<RefCell<Self>>, duration: Duration) -> Result<Sleep<C>, TimerError> {
+        // the real waker is registered on the first poll
+        let id = wheel.borrow_mut().init_timer(duration, Waker::noop())?;
+        Ok(Sleep {
+            wheel: wheel.clone(),
+            id,
+        })
+    }
+}
+
+impl<C: Clock> Future for Sleep<C> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.wheel.borrow_mut().poll(self.id, cx.waker())
+    }
+}
+
+impl<C: Clock> Drop for Sleep<C> {
+    fn drop(&mut self) {
+        self.wheel.borrow_mut().drop(self.id);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Clock, TimeWheel, TimerError, TimerId, TimerStore};
+use alloc::vec::Vec;
+use core::{task::Waker, time::Duration};
+
+/// remaining time of every waiting timer of a wheel, used to rebuild the schedule later on,
+/// for instance after a restart when the `serde` feature is enabled.
+///
+/// wakers can't be serialized and are not part of the snapshot, fresh ones have to be
+/// attached when restoring it with [`TimeWheel::restore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    pub timers: Vec<SnapshotTimer>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotTimer {
+    /// id of the timer in the wheel the snapshot was taken from
+    pub id: TimerId,
+    /// time left before the timer fires, rounded to the wheel's tick
+    pub remaining: Duration,
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> TimeWheel<T, C, N, S> {
+    pub fn snapshot(&self) -> Snapshot {
+        let timers = self
+            .buckets
+            .iter()
+            .map(|slot| SnapshotTimer {
+                id: self.storage.id(slot),
+                remaining: Duration::from_millis(
+                    (self.placements[slot].deadline - self.ticks) * self.tick_ms,
+                ),
+            })
+            .collect();
+        Snapshot { timers }
+    }
+
+    /// arms a timer for every timer of the snapshot with the waker returned by `waker_for`,
+    /// called with the id the timer had in the snapshotted wheel.
+    /// returns the `(snapshot id, new id)` pairs, nothing is armed if a timer can't be.
+    pub fn restore(
+        &mut self,
+        snapshot: &Snapshot,
+        mut waker_for: impl FnMut(TimerId) -> Waker,
+    ) -> Result<Vec<(TimerId, TimerId)>, TimerError>
+    where
+        T: Default,
+    {
+        let now = self.clock.now();
+        let mut deadlines = Vec::with_capacity(snapshot.timers.len());
+        for timer in &snapshot.timers {
+            let total_ms = Self::checked_ms(timer.remaining)?;
+            let target = C::add(now, timer.remaining);
+            deadlines.push((timer.id, self.deadline_from_ms(total_ms), target));
+        }
+        self.check_capacity(deadlines.len())?;
+
+        self.storage.reserve(deadlines.len());
+        Ok(deadlines
+            .into_iter()
+            .map(|(old_id, deadline, target)| {
+                let new_id = self.insert(deadline, target, now, &waker_for(old_id), T::default());
+                (old_id, new_id)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Clock, TimeWheel, TimerError, TimerId, TimerState, TimerStore};
+use alloc::vec::Vec;
+use core::{cell::RefCell, task::Waker, time::Duration};
+
+/// group of timers cancelled together, timers join it when armed with
+/// [`TimeWheel::init_timer_with_token`].
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    ids: RefCell<Vec<TimerId>>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// cancels every timer of the group still waiting, returns how many were cancelled.
+    /// fired timers are left to their owner, the group is empty afterwards.
+    pub fn cancel<T, C: Clock, const N: usize, S: TimerStore<T>>(
+        &self,
+        wheel: &mut TimeWheel<T, C, N, S>,
+    ) -> usize {
+        let mut cancelled = 0;
+        for id in self.ids.take() {
+            // fired timers stay with their owner, only waiting ones are dropped
+            if wheel.state(id) == Some(TimerState::Pending) {
+                wheel.drop(id);
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    /// number of timers armed under the token since it was last cancelled.
+    pub fn len(&self) -> usize {
+        self.ids.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.borrow().is_empty()
+    }
+}
+
+impl<T, C: Clock, const N: usize, S: TimerStore<T>> TimeWheel<T, C, N, S> {
+    /// same as [`TimeWheel::init_timer`] but the timer is cancelled along with the other timers
+    /// of `token` by [`CancelToken::cancel`].
+    pub fn init_timer_with_token(
+        &mut self,
+        duration: Duration,
+        waker: &Waker,
+        token: &CancelToken,
+    ) -> Result<TimerId, TimerError>
+    where
+        T: Default,
+    {
+        let id = self.init_timer(duration, waker)?;
+        token.ids.borrow_mut().push(id);
+        Ok(id)
+    }
+}
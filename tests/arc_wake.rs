@@ -0,0 +1,82 @@
+// wakers built the way `futures::task::waker_ref` builds them from an `ArcWake`: a raw waker
+// pointing at the `Arc` with a static vtable, borrowed without touching the reference count.
+
+use async_timers::{MockClock, TimeWheel};
+use std::mem::ManuallyDrop;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Poll, RawWaker, RawWakerVTable, Waker};
+use std::time::Duration;
+
+#[derive(Default)]
+struct CountingArcWaker {
+    wakes: AtomicUsize,
+    clones: AtomicUsize,
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_raw, wake_raw, wake_by_ref_raw, drop_raw);
+
+unsafe fn clone_raw(data: *const ()) -> RawWaker {
+    let arc = ManuallyDrop::new(unsafe { Arc::from_raw(data as *const CountingArcWaker) });
+    arc.clones.fetch_add(1, Ordering::SeqCst);
+    let _ = ManuallyDrop::new(Arc::clone(&arc));
+    RawWaker::new(data, &VTABLE)
+}
+
+unsafe fn wake_raw(data: *const ()) {
+    let arc = unsafe { Arc::from_raw(data as *const CountingArcWaker) };
+    arc.wakes.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe fn wake_by_ref_raw(data: *const ()) {
+    let arc = ManuallyDrop::new(unsafe { Arc::from_raw(data as *const CountingArcWaker) });
+    arc.wakes.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe fn drop_raw(data: *const ()) {
+    drop(unsafe { Arc::from_raw(data as *const CountingArcWaker) });
+}
+
+fn waker_ref(arc: &Arc<CountingArcWaker>) -> ManuallyDrop<Waker> {
+    let raw = RawWaker::new(Arc::as_ptr(arc) as *const (), &VTABLE);
+    ManuallyDrop::new(unsafe { Waker::from_raw(raw) })
+}
+
+#[test]
+fn test_arc_wake_waker_is_not_cloned_on_every_poll() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let task = Arc::new(CountingArcWaker::default());
+    let waker = waker_ref(&task);
+
+    let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert_eq!(task.clones.load(Ordering::SeqCst), 1);
+
+    for _ in 0..3 {
+        assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+    }
+    assert_eq!(task.clones.load(Ordering::SeqCst), 1);
+
+    wheel.advance_to(60);
+    assert_eq!(task.wakes.load(Ordering::SeqCst), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    // the stored clone was consumed by the wake
+    assert_eq!(Arc::strong_count(&task), 1);
+}
+
+#[test]
+fn test_arc_wake_waker_replaced_when_task_changes() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let first = Arc::new(CountingArcWaker::default());
+    let second = Arc::new(CountingArcWaker::default());
+
+    let id = wheel
+        .init_timer(Duration::from_millis(50), &waker_ref(&first))
+        .unwrap();
+    assert_eq!(wheel.poll(id, &waker_ref(&second)), Poll::Pending);
+    assert_eq!(second.clones.load(Ordering::SeqCst), 1);
+    assert_eq!(Arc::strong_count(&first), 1);
+
+    wheel.advance_to(60);
+    assert_eq!(first.wakes.load(Ordering::SeqCst), 0);
+    assert_eq!(second.wakes.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use async_timers::{CellTimeWheel, DropOutcome, MockClock};
+use common::make_waker;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Poll, Wake, Waker};
+use std::time::Duration;
+
+#[test]
+fn test_cell_wheel_from_shared_reference() {
+    let clock = MockClock::new();
+    let wheel = CellTimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.arm(Duration::from_millis(20), &waker).unwrap();
+    let cancelled = wheel.arm(Duration::from_millis(20), &waker).unwrap();
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+    assert_eq!(wheel.cancel(cancelled), DropOutcome::Cancelled);
+
+    clock.advance(Duration::from_millis(30));
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+thread_local! {
+    static CLOCK: MockClock = MockClock::new();
+    static WHEEL: CellTimeWheel<MockClock> = CLOCK.with(|clock| CellTimeWheel::new_with_clock(clock.clone()));
+}
+
+struct RearmingWaker;
+
+impl Wake for RearmingWaker {
+    fn wake(self: Arc<Self>) {
+        // Would panic if the wheel were still borrowed
+        let (_, waker) = make_waker();
+        WHEEL.with(|wheel| wheel.arm(Duration::from_millis(50), &waker).unwrap());
+    }
+}
+
+#[test]
+fn test_cell_wheel_waker_can_use_wheel() {
+    let waker = Waker::from(Arc::new(RearmingWaker));
+    WHEEL.with(|wheel| wheel.arm(Duration::from_millis(10), &waker).unwrap());
+
+    CLOCK.with(|clock| clock.advance(Duration::from_millis(20)));
+    assert_eq!(WHEEL.with(CellTimeWheel::tick), 1);
+
+    CLOCK.with(|clock| clock.advance(Duration::from_millis(60)));
+    assert_eq!(WHEEL.with(CellTimeWheel::tick), 1);
+}
+
+#[derive(Default)]
+struct TickingWaker {
+    wakes: AtomicUsize,
+    nested_fires: AtomicUsize,
+}
+
+impl Wake for TickingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+        let fired = WHEEL.with(CellTimeWheel::tick);
+        self.nested_fires.fetch_add(fired, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_cell_wheel_reentrant_tick_fires_once() {
+    let task = Arc::new(TickingWaker::default());
+    let waker = Waker::from(task.clone());
+    let (counter, other) = make_waker();
+    WHEEL.with(|wheel| {
+        wheel.arm(Duration::from_millis(10), &waker).unwrap();
+        wheel.arm(Duration::from_millis(10), &waker).unwrap();
+        wheel.arm(Duration::from_millis(10), &other).unwrap();
+    });
+
+    // the bucket is drained before any waker runs, the nested ticks find nothing left
+    CLOCK.with(|clock| clock.advance(Duration::from_millis(20)));
+    assert_eq!(WHEEL.with(CellTimeWheel::tick), 3);
+    assert_eq!(task.wakes.load(Ordering::SeqCst), 2);
+    assert_eq!(task.nested_fires.load(Ordering::SeqCst), 0);
+    assert_eq!(counter.count(), 1);
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "futures")]
+
+mod common;
+
+use async_timers::{MockClock, TimeWheel};
+use common::make_waker;
+use futures_core::Stream;
+use std::cell::RefCell;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[test]
+fn test_interval_stream_yields_on_every_fire() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+    let (counter, waker) = make_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut stream = pin!(TimeWheel::interval_stream(&wheel, Duration::from_millis(20)).unwrap());
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+
+    for fires in 1..=3 {
+        wheel.borrow_mut().advance_to(10 + 20 * fires as u64);
+        assert_eq!(counter.count(), fires);
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(())));
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+    }
+}
+
+#[test]
+fn test_interval_stream_stop_ends_stream() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+    let (_, waker) = make_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut stream = pin!(TimeWheel::interval_stream(&wheel, Duration::from_millis(20)).unwrap());
+    stream.as_mut().stop();
+
+    assert!(wheel.borrow().is_empty());
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn test_interval_stream_drop_cancels_timer() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+
+    let stream = TimeWheel::interval_stream(&wheel, Duration::from_millis(20)).unwrap();
+    assert_eq!(wheel.borrow().len(), 1);
+
+    drop(stream);
+    assert!(wheel.borrow().is_empty());
+}
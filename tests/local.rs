@@ -0,0 +1,37 @@
+#![cfg(feature = "driver")]
+
+use async_timers::{TimerError, block_on, local_wheel, sleep, try_sleep};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_block_on_sleep() {
+    let start = Instant::now();
+    block_on(async {
+        sleep(Duration::from_millis(30)).await;
+        sleep(Duration::from_millis(20)).await;
+    });
+
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(50), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+    assert!(local_wheel().borrow().is_empty());
+}
+
+#[test]
+fn test_block_on_ready_future() {
+    assert_eq!(block_on(async { 7 }), 7);
+}
+
+#[test]
+fn test_try_sleep_reports_too_long_duration() {
+    assert!(matches!(
+        try_sleep(Duration::from_hours(25)),
+        Err(TimerError::DurationTooLong(_))
+    ));
+}
+
+#[test]
+#[should_panic]
+fn test_sleep_panics_on_too_long_duration() {
+    drop(sleep(Duration::from_hours(25)));
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use async_timers::{Clock, DropOutcome, SharedTimeWheel, TimerHandle, TimerId};
+use common::make_waker;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+// MockClock is not Send, the shared wheel needs a clock that can cross threads
+#[derive(Clone, Default)]
+struct AtomicClock(Arc<AtomicU64>);
+
+impl Clock for AtomicClock {
+    type Instant = u64;
+
+    fn now(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn duration_between(earlier: u64, later: u64) -> Duration {
+        Duration::from_millis(later.saturating_sub(earlier))
+    }
+
+    fn add(instant: u64, duration: Duration) -> u64 {
+        instant + duration.as_millis() as u64
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.0
+            .fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_shared_wheel_arm_from_other_thread() {
+    let wheel = SharedTimeWheel::new_with_clock(AtomicClock::default());
+    let (counter, waker) = make_waker();
+
+    let handle = {
+        let wheel = wheel.clone();
+        thread::spawn(move || wheel.arm(Duration::from_millis(20), &waker).unwrap())
+    };
+    let id = handle.join().unwrap();
+
+    assert_eq!(wheel.advance_to(35), 1);
+    assert_eq!(counter.count(), 1);
+
+    let (_, waker) = make_waker();
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_shared_wheel_cancel() {
+    let wheel = SharedTimeWheel::new_with_clock(AtomicClock::default());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.arm(Duration::from_millis(20), &waker).unwrap();
+    assert_eq!(wheel.cancel(id), DropOutcome::Cancelled);
+
+    assert_eq!(wheel.advance_to(35), 0);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn test_timer_handle_cancels_on_drop() {
+    let wheel = SharedTimeWheel::new_with_clock(AtomicClock::default());
+    let (counter, waker) = make_waker();
+
+    let kept = wheel.arm_handle(Duration::from_millis(20), &waker).unwrap();
+    let dropped = wheel.arm_handle(Duration::from_millis(20), &waker).unwrap();
+    let dropped_id = dropped.id();
+    assert_eq!(kept.poll(&waker), Poll::Pending);
+    drop(dropped);
+    assert_eq!(wheel.cancel(dropped_id), DropOutcome::Unknown);
+
+    assert_eq!(wheel.advance_to(35), 1);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(kept.poll(&waker), Poll::Ready(()));
+}
+
+struct RearmingWaker {
+    wheel: SharedTimeWheel<AtomicClock>,
+    rearmed: Mutex<Vec<TimerId>>,
+}
+
+impl Wake for RearmingWaker {
+    fn wake(self: Arc<Self>) {
+        // Would deadlock if the wheel were still locked
+        let (_, waker) = make_waker();
+        let id = self.wheel.arm(Duration::from_millis(50), &waker).unwrap();
+        self.rearmed.lock().unwrap().push(id);
+    }
+}
+
+#[test]
+fn test_shared_wheel_waker_can_use_wheel() {
+    let wheel = SharedTimeWheel::new_with_clock(AtomicClock::default());
+    let rearming = Arc::new(RearmingWaker {
+        wheel: wheel.clone(),
+        rearmed: Mutex::new(Vec::new()),
+    });
+    let waker = Waker::from(rearming.clone());
+
+    wheel.arm(Duration::from_millis(10), &waker).unwrap();
+
+    assert_eq!(wheel.advance_to(25), 1);
+    assert_eq!(rearming.rearmed.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_shared_wheel_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedTimeWheel>();
+    assert_send_sync::<TimerHandle>();
+    assert_send_sync::<SharedTimeWheel<AtomicClock>>();
+}
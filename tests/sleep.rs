@@ -0,0 +1,48 @@
+mod common;
+
+use async_timers::{MockClock, TimeWheel};
+use common::make_waker;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[test]
+fn test_sleep_future_completes() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+    let (counter, waker) = make_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut fut = pin!(TimeWheel::sleep(&wheel, Duration::from_millis(20)).unwrap());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+
+    wheel.borrow_mut().advance_to(35);
+
+    // the waker registered by the first poll is the one called
+    assert_eq!(counter.count(), 1);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+}
+
+#[test]
+fn test_sleep_drop_cancels_timer() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+    let (counter, waker) = make_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    {
+        let mut fut = pin!(TimeWheel::sleep(&wheel, Duration::from_millis(20)).unwrap());
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    }
+
+    wheel.borrow_mut().advance_to(35);
+
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn test_sleep_too_long() {
+    let wheel = Rc::new(RefCell::new(TimeWheel::new_with_clock(MockClock::new())));
+    assert!(TimeWheel::sleep(&wheel, Duration::from_hours(24)).is_err());
+}
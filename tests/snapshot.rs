@@ -0,0 +1,121 @@
+mod common;
+
+use async_timers::{MockClock, Snapshot, SnapshotTimer, TimeWheel, TimerId};
+use common::make_waker;
+use std::time::Duration;
+
+fn id(index: usize) -> TimerId {
+    TimerId {
+        index,
+        generation: 0,
+    }
+}
+
+#[test]
+fn test_snapshot_empty_wheel() {
+    let wheel = TimeWheel::new();
+    assert!(wheel.snapshot().timers.is_empty());
+}
+
+#[test]
+fn test_snapshot_records_remaining_time() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id_ms = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let id_s = wheel
+        .init_timer(Duration::from_millis(1500), &waker)
+        .unwrap();
+    let id_h = wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+    let cancelled = wheel.init_timer(Duration::from_millis(80), &waker).unwrap();
+    wheel.drop(cancelled);
+
+    wheel.advance_to(20);
+
+    let mut timers = wheel.snapshot().timers;
+    timers.sort_by_key(|t| t.id);
+    assert_eq!(
+        timers,
+        vec![
+            SnapshotTimer {
+                id: id_ms,
+                remaining: Duration::from_millis(30),
+            },
+            SnapshotTimer {
+                id: id_s,
+                remaining: Duration::from_millis(1480),
+            },
+            SnapshotTimer {
+                id: id_h,
+                remaining: Duration::from_hours(2) - Duration::from_millis(20),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_restore_rearms_timers() {
+    let (counter, waker) = make_waker();
+    let snapshot = Snapshot {
+        timers: vec![
+            SnapshotTimer {
+                id: id(4),
+                remaining: Duration::from_millis(30),
+            },
+            SnapshotTimer {
+                id: id(9),
+                remaining: Duration::from_millis(500),
+            },
+        ],
+    };
+
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let mut requested = Vec::new();
+    let ids = wheel
+        .restore(&snapshot, |id| {
+            requested.push(id);
+            waker.clone()
+        })
+        .unwrap();
+
+    assert_eq!(requested, vec![id(4), id(9)]);
+    assert_eq!(ids, vec![(id(4), id(0)), (id(9), id(1))]);
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(30)));
+
+    wheel.advance_to(600);
+    assert_eq!(counter.count(), 2);
+}
+
+#[test]
+fn test_restore_rejects_too_long_duration() {
+    let (_, waker) = make_waker();
+    let snapshot = Snapshot {
+        timers: vec![SnapshotTimer {
+            id: id(0),
+            remaining: Duration::from_hours(30),
+        }],
+    };
+
+    let mut wheel = TimeWheel::new();
+    assert!(wheel.restore(&snapshot, |_| waker.clone()).is_err());
+    assert!(wheel.is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_snapshot_serde_roundtrip() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(90), &waker).unwrap();
+
+    let snapshot = wheel.snapshot();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: Snapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, snapshot);
+
+    let mut wheel = TimeWheel::new();
+    wheel.restore(&restored, |_| waker.clone()).unwrap();
+    assert_eq!(wheel.snapshot().timers.len(), 2);
+}
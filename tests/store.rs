@@ -0,0 +1,172 @@
+// a custom storage backend: a plain vector of slots with a free list, counting the timers it
+// stored and released, plugged into the wheel through `TimerStore`.
+
+mod common;
+
+use async_timers::{DropOutcome, MockClock, TimeWheel, Timer, TimerId, TimerState, TimerStore};
+use common::make_waker;
+use std::task::Poll;
+use std::time::Duration;
+
+#[derive(Clone)]
+struct VecStore<T> {
+    slots: Vec<(u32, Option<Timer<T>>)>,
+    free: Vec<usize>,
+    inserted: usize,
+    removed: usize,
+}
+
+impl<T> Default for VecStore<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            inserted: 0,
+            removed: 0,
+        }
+    }
+}
+
+impl<T> TimerStore<T> for VecStore<T> {
+    fn insert(&mut self, timer: Timer<T>) -> TimerId {
+        self.inserted += 1;
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.slots.push((0, None));
+            self.slots.len() - 1
+        });
+        self.slots[index].1 = Some(timer);
+        self.id(index)
+    }
+
+    fn remove(&mut self, id: TimerId) -> Option<Timer<T>> {
+        self.get(id)?;
+        self.removed += 1;
+        let (generation, timer) = &mut self.slots[id.index];
+        *generation = generation.wrapping_add(1);
+        self.free.push(id.index);
+        timer.take()
+    }
+
+    fn get(&self, id: TimerId) -> Option<&Timer<T>> {
+        match self.slots.get(id.index)? {
+            (generation, timer) if *generation == id.generation => timer.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn get_mut(&mut self, id: TimerId) -> Option<&mut Timer<T>> {
+        match self.slots.get_mut(id.index)? {
+            (generation, timer) if *generation == id.generation => timer.as_mut(),
+            _ => None,
+        }
+    }
+
+    fn id(&self, index: usize) -> TimerId {
+        TimerId {
+            index,
+            generation: self.slots[index].0,
+        }
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&mut Timer<T>) -> bool) {
+        for index in 0..self.slots.len() {
+            let id = self.id(index);
+            if self.get_mut(id).is_some_and(|timer| !keep(timer)) {
+                self.remove(id);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            self.remove(self.id(index));
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.slots.reserve(additional);
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+    }
+}
+
+#[test]
+fn test_custom_store_drives_the_wheel() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::<(), MockClock, 8, VecStore<()>>::from_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let first = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let second = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    let cancelled = wheel.init_timer(Duration::from_millis(80), &waker).unwrap();
+    assert_eq!(wheel.drop(cancelled), DropOutcome::Cancelled);
+    assert_eq!(wheel.len(), 2);
+
+    clock.advance(Duration::from_millis(100));
+    wheel.tick();
+
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(first, &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(second, &waker), Poll::Pending);
+    assert_eq!(wheel.state(first), Some(TimerState::Fired));
+    assert_eq!(wheel.storage().inserted, 3);
+    assert_eq!(wheel.storage().removed, 1);
+
+    // the slot of the cancelled timer is reused with a new generation
+    let reused = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert_eq!(reused.index, cancelled.index);
+    assert_eq!(wheel.state(cancelled), None);
+}
+
+#[test]
+fn test_wheel_from_configured_store() {
+    let mut store = VecStore::default();
+    store.reserve(64);
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::<(), MockClock, 8, VecStore<()>>::from_parts(clock.clone(), store);
+    assert!(wheel.capacity() >= 64);
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    clock.advance(Duration::from_millis(100));
+    wheel.tick();
+    assert_eq!(counter.count(), 1);
+
+    // the waiting timer is cancelled and the store comes back empty
+    let (clock, store) = wheel.into_parts();
+    assert!(store.is_empty());
+    assert_eq!(store.inserted, 2);
+
+    let mut wheel = TimeWheel::<(), MockClock, 8, VecStore<()>>::from_parts(clock.clone(), store);
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    clock.advance(Duration::from_millis(100));
+    wheel.tick();
+    assert_eq!(counter.count(), 2);
+    assert_eq!(wheel.storage().inserted, 3);
+}
+
+#[test]
+#[should_panic]
+fn test_from_parts_rejects_a_store_holding_timers() {
+    let mut wheel = TimeWheel::<(), MockClock, 8, VecStore<()>>::from_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+
+    let store = wheel.storage().clone();
+    let _ = TimeWheel::<(), MockClock, 8, VecStore<()>>::from_parts(MockClock::new(), store);
+}
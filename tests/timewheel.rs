@@ -1,9 +1,18 @@
 mod common;
 
-use async_timers::{DurationTooLong, TimeWheel};
+use async_timers::{
+    BucketLocation, CancelToken, CatchUpPolicy, Clock, DropOutcome, DurationTooLong, Level,
+    MockClock, PollRemaining, RoundingPolicy, TickClock, TickOutcome, TimeWheel, TimeWheelStats,
+    TimerError, TimerId, TimerState,
+};
 use common::make_waker;
-use std::task::Poll;
-use std::thread::sleep;
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
 use std::time::Duration;
 
 #[test]
@@ -24,10 +33,10 @@ fn test_init_timer_returns_id() {
     let (_, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
-    assert_eq!(id, 0);
+    assert_eq!(id.index, 0);
 
     let id2 = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
-    assert_eq!(id2, 1);
+    assert_eq!(id2.index, 1);
 }
 
 #[test]
@@ -36,7 +45,13 @@ fn test_duration_too_long_rejected() {
     let (_, waker) = make_waker();
 
     let result = wheel.init_timer(Duration::from_hours(24), &waker);
-    assert_eq!(result, Err(DurationTooLong));
+    assert_eq!(
+        result,
+        Err(TimerError::DurationTooLong(DurationTooLong {
+            requested: Duration::from_hours(24),
+            max: Duration::from_hours(24),
+        }))
+    );
 }
 
 #[test]
@@ -49,6 +64,55 @@ fn test_duration_at_limit() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_duration_too_long_display() {
+    let err = TimerError::from(DurationTooLong {
+        requested: Duration::from_hours(30),
+        max: Duration::from_hours(24),
+    });
+    assert_eq!(
+        err.to_string(),
+        "duration of 108000s is not under the 86400s limit of the wheel"
+    );
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn test_extreme_durations_rejected() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    // 2^64ms + 10ms would wrap to 10ms if truncated
+    let wrapping = Duration::from_millis(u64::MAX) + Duration::from_millis(11);
+    for duration in [Duration::MAX, Duration::from_secs(u64::MAX), wrapping] {
+        assert_eq!(
+            wheel.init_timer(duration, &waker),
+            Err(TimerError::DurationTooLong(DurationTooLong {
+                requested: duration,
+                max: Duration::from_hours(24),
+            })),
+            "{duration:?}"
+        );
+        assert_eq!(
+            wheel.init_interval(duration, &waker),
+            Err(TimerError::DurationTooLong(DurationTooLong {
+                requested: duration,
+                max: Duration::from_hours(24),
+            })),
+            "{duration:?}"
+        );
+    }
+    assert!(wheel.is_empty());
+
+    let limit = Duration::from_hours(24);
+    assert!(
+        wheel
+            .init_timer(limit - Duration::from_millis(1), &waker)
+            .is_ok()
+    );
+    assert!(wheel.init_timer(limit, &waker).is_err());
+}
+
 #[test]
 fn test_zero_duration_timer() {
     let mut wheel = TimeWheel::new();
@@ -56,29 +120,59 @@ fn test_zero_duration_timer() {
 
     let id = wheel.init_timer(Duration::ZERO, &waker).unwrap();
 
-    // Timer at current bucket should fire on next tick
-    sleep(Duration::from_millis(15));
-    wheel.tick();
+    // ready right away, no tick needed and nothing to wake
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert_eq!(counter.count(), 0);
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_zero_duration_timer_is_fired_and_stays_ready() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::ZERO, &waker).unwrap();
+    assert_eq!(wheel.state(id), Some(TimerState::Fired));
+    assert_eq!(wheel.next_deadline(), None);
+    assert_eq!(wheel.unclaimed_fired(), 1);
+
+    wheel.advance_to(100);
+    assert_eq!(counter.count(), 0);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_sub_tick_duration_timer_waits_for_next_tick() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    // unlike a zero duration, a non zero duration shorter than a tick is scheduled
+    let id = wheel.init_timer(Duration::from_millis(1), &waker).unwrap();
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(10)));
 
+    wheel.advance_to(10);
     assert_eq!(counter.count(), 1);
     assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
 }
 
 #[test]
 fn test_timer_fires_at_ms_level() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
 
     // Timer should not fire before its time
-    sleep(Duration::from_millis(15));
+    clock.advance(Duration::from_millis(19));
     wheel.tick();
     assert_eq!(counter.count(), 0);
     assert_eq!(wheel.poll(id, &waker), Poll::Pending);
 
-    // Timer should fire after sufficient time
-    sleep(Duration::from_millis(20));
+    // Timer fires once the tick after its deadline is processed
+    clock.advance(Duration::from_millis(11));
     wheel.tick();
     assert_eq!(counter.count(), 1);
     assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
@@ -86,13 +180,14 @@ fn test_timer_fires_at_ms_level() {
 
 #[test]
 fn test_timer_fires_at_ms_boundary() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     // 90ms is close to the ms-level boundary (100ms)
     let id = wheel.init_timer(Duration::from_millis(90), &waker).unwrap();
 
-    sleep(Duration::from_millis(100));
+    clock.advance(Duration::from_millis(100));
     wheel.tick();
 
     assert_eq!(counter.count(), 1);
@@ -101,7 +196,8 @@ fn test_timer_fires_at_ms_boundary() {
 
 #[test]
 fn test_timer_fires_at_second_level() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     // 300ms should be in the second-level bucket (ms threshold is 200ms)
@@ -109,11 +205,13 @@ fn test_timer_fires_at_second_level() {
         .init_timer(Duration::from_millis(300), &waker)
         .unwrap();
 
-    // Verify it's not in ms level by checking deadline is calculated correctly
-    assert!(wheel.next_deadline().unwrap() >= Duration::from_millis(200));
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(300)));
+
+    clock.advance(Duration::from_millis(300));
+    wheel.tick();
+    assert_eq!(counter.count(), 0);
 
-    // Process ticks until timer fires
-    sleep(Duration::from_millis(350));
+    clock.advance(Duration::from_millis(10));
     wheel.tick();
 
     assert_eq!(counter.count(), 1);
@@ -122,20 +220,19 @@ fn test_timer_fires_at_second_level() {
 
 #[test]
 fn test_timer_at_one_second() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
 
-    // Verify timer is placed in second-level bucket (deadline >= 200ms threshold)
-    let deadline = wheel.next_deadline().unwrap();
-    assert!(
-        deadline >= Duration::from_millis(200),
-        "1s timer should be in second-level bucket"
-    );
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_secs(1)));
+
+    clock.advance(Duration::from_secs(1));
+    wheel.tick();
+    assert_eq!(counter.count(), 0);
 
-    // Should fire after 1 second total
-    sleep(Duration::from_millis(1100));
+    clock.advance(Duration::from_millis(10));
     wheel.tick();
     assert_eq!(counter.count(), 1);
     assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
@@ -156,7 +253,8 @@ fn test_timer_registered_at_hour_level() {
 
 #[test]
 fn test_multiple_timers_same_bucket() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter1, waker1) = make_waker();
     let (counter2, waker2) = make_waker();
 
@@ -168,7 +266,7 @@ fn test_multiple_timers_same_bucket() {
         .unwrap();
 
     // Both should be in the same bucket (10ms granularity)
-    sleep(Duration::from_millis(35));
+    clock.advance(Duration::from_millis(30));
     wheel.tick();
 
     assert_eq!(counter1.count(), 1);
@@ -179,7 +277,8 @@ fn test_multiple_timers_same_bucket() {
 
 #[test]
 fn test_multiple_timers_different_buckets() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter1, waker1) = make_waker();
     let (counter2, waker2) = make_waker();
 
@@ -189,7 +288,7 @@ fn test_multiple_timers_different_buckets() {
         .unwrap();
 
     // First timer should fire first
-    sleep(Duration::from_millis(10));
+    clock.advance(Duration::from_millis(10));
     wheel.tick();
 
     assert_eq!(counter1.count(), 1);
@@ -198,7 +297,7 @@ fn test_multiple_timers_different_buckets() {
     assert_eq!(wheel.poll(id2, &waker2), Poll::Pending);
 
     // Second timer should fire after more time
-    sleep(Duration::from_millis(10));
+    clock.advance(Duration::from_millis(10));
     wheel.tick();
 
     assert_eq!(counter2.count(), 1);
@@ -207,7 +306,8 @@ fn test_multiple_timers_different_buckets() {
 
 #[test]
 fn test_multiple_timers_different_levels() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter_ms, waker_ms) = make_waker();
     let (counter_s, waker_s) = make_waker();
 
@@ -219,7 +319,7 @@ fn test_multiple_timers_different_levels() {
         .unwrap();
 
     // MS level timer should fire first
-    sleep(Duration::from_millis(60));
+    clock.advance(Duration::from_millis(60));
     wheel.tick();
 
     assert_eq!(counter_ms.count(), 1);
@@ -227,7 +327,11 @@ fn test_multiple_timers_different_levels() {
     assert_eq!(wheel.poll(id_ms, &waker_ms), Poll::Ready(()));
 
     // S level timer should fire after cascade
-    sleep(Duration::from_millis(300));
+    clock.advance(Duration::from_millis(240));
+    wheel.tick();
+    assert_eq!(counter_s.count(), 0);
+
+    clock.advance(Duration::from_millis(10));
     wheel.tick();
 
     assert_eq!(counter_s.count(), 1);
@@ -236,7 +340,8 @@ fn test_multiple_timers_different_levels() {
 
 #[test]
 fn test_cancel_timer_before_fire() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
@@ -245,7 +350,7 @@ fn test_cancel_timer_before_fire() {
     wheel.drop(id);
 
     // Let time pass and tick
-    sleep(Duration::from_millis(60));
+    clock.advance(Duration::from_millis(60));
     wheel.tick();
 
     // Waker should not have been called
@@ -254,21 +359,34 @@ fn test_cancel_timer_before_fire() {
 
 #[test]
 fn test_cancel_timer_idempotent() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
 
     // Cancel multiple times should not panic
-    wheel.drop(id);
-    wheel.drop(id);
+    assert_eq!(wheel.drop(id), DropOutcome::Cancelled);
+    assert_eq!(wheel.drop(id), DropOutcome::Unknown);
 
-    sleep(Duration::from_millis(60));
+    clock.advance(Duration::from_millis(60));
     wheel.tick();
 
     assert_eq!(counter.count(), 0);
 }
 
+#[test]
+fn test_drop_fired_timer_reports_it() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.advance_to(30);
+
+    assert_eq!(wheel.drop(id), DropOutcome::AlreadyFired);
+    assert_eq!(wheel.drop(id), DropOutcome::Unknown);
+}
+
 #[test]
 fn test_poll_cancelled_timer() {
     let mut wheel = TimeWheel::new();
@@ -282,6 +400,122 @@ fn test_poll_cancelled_timer() {
     assert_eq!(result, Poll::Ready(()));
 }
 
+#[test]
+fn test_poll_unknown_id() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    // An id that was never handed out is treated as already gone
+    let id = TimerId {
+        index: 42,
+        generation: 0,
+    };
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_poll_fired_timer_repeatedly() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.advance_to(30);
+
+    // executors may poll a completed future again
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+
+    // and once its slot is released
+    wheel.drop(id);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_ready_timers_never_linger_in_buckets() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    // ready without a tick, then released while its neighbours wait to cascade
+    let ready = wheel.init_timer(Duration::ZERO, &waker).unwrap();
+    assert_eq!(wheel.poll(ready, &waker), Poll::Ready(()));
+    wheel.drop(ready);
+
+    let fired = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    let cascading = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    wheel.advance_to(30);
+    assert_eq!(wheel.poll(fired, &waker), Poll::Ready(()));
+    wheel.drop(fired);
+
+    // the released slots are reused by timers of other buckets
+    let reused = wheel.init_timer(Duration::from_secs(2), &waker).unwrap();
+    assert_eq!(reused.index, fired.index);
+
+    wheel.advance_to(3_000);
+    assert_eq!(counter.count(), 3);
+    assert_eq!(wheel.poll(cascading, &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(reused, &waker), Poll::Ready(()));
+    assert_eq!(wheel.to_string(), "ms[] s[] m[] h[]");
+}
+
+#[test]
+fn test_stale_id_does_not_touch_reused_slot() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let stale = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.drop(stale);
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    assert_eq!(id.index, stale.index);
+    assert_ne!(id, stale);
+
+    // the stale id is treated as already gone
+    assert_eq!(wheel.poll(stale, &waker), Poll::Ready(()));
+    wheel.drop(stale);
+    assert_eq!(wheel.len(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+
+    assert_eq!(wheel.advance_to_collect(30), vec![id]);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_ids_from_before_clear_are_stale() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let stale = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.clear();
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    assert_eq!(id.index, stale.index);
+
+    wheel.drop(stale);
+    assert_eq!(wheel.len(), 1);
+}
+
+#[test]
+fn test_drop_unknown_id() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    // Should not panic nor affect the live timer
+    let outcome = wheel.drop(TimerId {
+        index: 42,
+        generation: 0,
+    });
+    assert_eq!(outcome, DropOutcome::Unknown);
+    assert_eq!(wheel.len(), 1);
+
+    clock.advance(Duration::from_millis(30));
+    wheel.tick();
+    assert_eq!(counter.count(), 1);
+}
+
 #[test]
 fn test_poll_pending_timer() {
     let mut wheel = TimeWheel::new();
@@ -297,7 +531,8 @@ fn test_poll_pending_timer() {
 
 #[test]
 fn test_poll_updates_waker() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter1, waker1) = make_waker();
     let (counter2, waker2) = make_waker();
 
@@ -309,7 +544,8 @@ fn test_poll_updates_waker() {
     let _ = wheel.poll(id, &waker2);
 
     // Let timer fire
-    sleep(Duration::from_millis(40));
+    clock.advance(Duration::from_millis(40));
+    assert_eq!(counter2.count(), 0);
     wheel.tick();
 
     // New waker should have been called, not the original
@@ -368,14 +604,15 @@ fn test_next_deadline_multiple_timers_returns_soonest() {
 
 #[test]
 fn test_next_deadline_updates_after_fire() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (_, waker) = make_waker();
 
     wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
     wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
 
     // Fire first timer
-    sleep(Duration::from_millis(30));
+    clock.advance(Duration::from_millis(30));
     wheel.tick();
 
     let deadline = wheel.next_deadline().unwrap();
@@ -385,12 +622,13 @@ fn test_next_deadline_updates_after_fire() {
 
 #[test]
 fn test_next_deadline_returns_none_after_all_fired() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (_, waker) = make_waker();
 
     wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
 
-    sleep(Duration::from_millis(30));
+    clock.advance(Duration::from_millis(30));
     wheel.tick();
 
     // Note: occupied bit might still be set even though timers are fired
@@ -404,7 +642,8 @@ fn test_next_deadline_returns_none_after_all_fired() {
 
 #[test]
 fn test_tick_processes_multiple_ticks() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter1, waker1) = make_waker();
     let (counter2, waker2) = make_waker();
 
@@ -416,7 +655,7 @@ fn test_tick_processes_multiple_ticks() {
         .unwrap();
 
     // Sleep long enough for both to fire
-    sleep(Duration::from_millis(50));
+    clock.advance(Duration::from_millis(50));
     wheel.tick();
 
     // Both should have fired in single tick() call
@@ -426,16 +665,18 @@ fn test_tick_processes_multiple_ticks() {
 
 #[test]
 fn test_tick_no_timers() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
 
     // Should not panic
-    sleep(Duration::from_millis(20));
+    clock.advance(Duration::from_millis(20));
     wheel.tick();
 }
 
 #[test]
 fn test_rapid_ticks() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     // Use ms-level timer to avoid cascade timing issues
@@ -446,7 +687,7 @@ fn test_rapid_ticks() {
     // Call tick many times before timer should fire (total ~30ms)
     for _ in 0..10 {
         wheel.tick();
-        sleep(Duration::from_millis(3));
+        clock.advance(Duration::from_millis(3));
     }
 
     assert_eq!(
@@ -456,7 +697,7 @@ fn test_rapid_ticks() {
     );
 
     // Now let it fire (sleep enough for >100ms total from start)
-    sleep(Duration::from_millis(120));
+    clock.advance(Duration::from_millis(120));
     wheel.tick();
 
     assert_eq!(counter.count(), 1);
@@ -464,7 +705,8 @@ fn test_rapid_ticks() {
 
 #[test]
 fn test_cascade_from_seconds_to_ms() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     // Timer in second-level bucket
@@ -473,7 +715,7 @@ fn test_cascade_from_seconds_to_ms() {
         .unwrap();
 
     // Process ticks to trigger cascade
-    sleep(Duration::from_millis(200));
+    clock.advance(Duration::from_millis(200));
     wheel.tick();
 
     assert_eq!(counter.count(), 1);
@@ -482,13 +724,14 @@ fn test_cascade_from_seconds_to_ms() {
 
 #[test]
 fn test_timer_at_bucket_boundary() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     // Exactly at 10ms boundary
     let id = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
 
-    sleep(Duration::from_millis(20));
+    clock.advance(Duration::from_millis(20));
     wheel.tick();
 
     assert_eq!(counter.count(), 1);
@@ -497,7 +740,8 @@ fn test_timer_at_bucket_boundary() {
 
 #[test]
 fn test_many_timers_in_one_bucket() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let wakers: Vec<_> = (0..20).map(|_| make_waker()).collect();
 
     let ids: Vec<_> = wakers
@@ -505,7 +749,7 @@ fn test_many_timers_in_one_bucket() {
         .map(|(_, w)| wheel.init_timer(Duration::from_millis(20), w).unwrap())
         .collect();
 
-    sleep(Duration::from_millis(35));
+    clock.advance(Duration::from_millis(35));
     wheel.tick();
 
     // All should have fired
@@ -515,20 +759,49 @@ fn test_many_timers_in_one_bucket() {
     }
 }
 
+#[test]
+fn test_wake_coalescing_wakes_a_task_once_per_bucket() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_wake_coalescing(true);
+    let (counter, waker) = make_waker();
+    let (other_counter, other_waker) = make_waker();
+
+    let mut ids: Vec<_> = (0..20)
+        .map(|_| wheel.init_timer(Duration::from_millis(20), &waker).unwrap())
+        .collect();
+    ids.push(
+        wheel
+            .init_timer(Duration::from_millis(20), &other_waker)
+            .unwrap(),
+    );
+    ids.push(wheel.init_timer(Duration::from_millis(40), &waker).unwrap());
+
+    assert_eq!(wheel.advance_to_collect(30), ids[..21]);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(other_counter.count(), 1);
+    for id in &ids[..21] {
+        assert_eq!(wheel.poll(*id, &waker), Poll::Ready(()));
+    }
+
+    // a later bucket wakes the task again
+    wheel.advance_to(50);
+    assert_eq!(counter.count(), 2);
+}
+
 #[test]
 fn test_timer_fires_exactly_once() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter, waker) = make_waker();
 
     wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
 
     // Fire the timer
-    sleep(Duration::from_millis(30));
+    clock.advance(Duration::from_millis(30));
     wheel.tick();
 
     // Tick more times
     for _ in 0..10 {
-        sleep(Duration::from_millis(15));
+        clock.advance(Duration::from_millis(15));
         wheel.tick();
     }
 
@@ -538,7 +811,8 @@ fn test_timer_fires_exactly_once() {
 
 #[test]
 fn test_interleaved_register_and_tick() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (counter1, waker1) = make_waker();
     let (counter2, waker2) = make_waker();
 
@@ -547,7 +821,7 @@ fn test_interleaved_register_and_tick() {
         .init_timer(Duration::from_millis(50), &waker1)
         .unwrap();
 
-    sleep(Duration::from_millis(30));
+    clock.advance(Duration::from_millis(30));
     wheel.tick();
 
     // First timer should not have fired yet
@@ -559,19 +833,52 @@ fn test_interleaved_register_and_tick() {
         .unwrap();
 
     // Let first timer fire
-    sleep(Duration::from_millis(40));
+    clock.advance(Duration::from_millis(40));
     wheel.tick();
 
     assert_eq!(counter1.count(), 1, "First timer should have fired");
     assert_eq!(counter2.count(), 0, "Second timer fired too early");
 
     // Let second timer fire
-    sleep(Duration::from_millis(50));
+    clock.advance(Duration::from_millis(50));
     wheel.tick();
 
     assert_eq!(counter2.count(), 1, "Second timer should have fired");
 }
 
+// ============================================================================
+// cancel token tests
+// ============================================================================
+
+#[test]
+fn test_cancel_token_cancels_group() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+    let token = CancelToken::new();
+
+    let fired = wheel
+        .init_timer_with_token(Duration::from_millis(50), &waker, &token)
+        .unwrap();
+    let waiting = wheel
+        .init_timer_with_token(Duration::from_millis(500), &waker, &token)
+        .unwrap();
+    let other = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    assert_eq!(token.len(), 2);
+
+    wheel.advance_to(60);
+    assert_eq!(token.cancel(&mut wheel), 1);
+    assert!(token.is_empty());
+    assert_eq!(wheel.state(waiting), None);
+    // the fired timer stays with its owner
+    assert_eq!(wheel.state(fired), Some(TimerState::Fired));
+
+    wheel.advance_to(510);
+    assert_eq!(counter.count(), 2);
+    assert_eq!(wheel.poll(other, &waker), Poll::Ready(()));
+}
+
 // ============================================================================
 // next_deadline tests
 // ============================================================================
@@ -581,12 +888,11 @@ fn test_next_deadline_zero_duration_timer() {
     let mut wheel = TimeWheel::new();
     let (_, waker) = make_waker();
 
-    // Zero duration timer goes into current bucket
+    // Zero duration timer is ready at once and never enters the wheel
     wheel.init_timer(Duration::ZERO, &waker).unwrap();
 
-    // Timer at current bucket (offset 0) should return None per the implementation
     let deadline = wheel.next_deadline();
-    assert_eq!(deadline, None, "Zero-offset timer should return None");
+    assert_eq!(deadline, None, "Zero-offset timer should not be scheduled");
 }
 
 #[test]
@@ -610,21 +916,52 @@ fn test_next_deadline_timer_in_second_level() {
 
 #[test]
 fn test_next_deadline_timer_in_hour_level() {
-    let mut wheel = TimeWheel::new();
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
     let (_, waker) = make_waker();
 
     // 2 hours is in the hour-level bucket
     wheel.init_timer(Duration::from_secs(7200), &waker).unwrap();
 
-    let deadline = wheel.next_deadline().unwrap();
-    // Should be at least 60 seconds (full ms + s levels need to cascade)
-    assert!(
-        deadline >= Duration::from_secs(60),
-        "Hour-level timer deadline should be >= 60s, got {:?}",
-        deadline
+    // reported from the deadline itself, not shortened by the hands of the finer levels
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_secs(7200)));
+
+    // and still once the ms and second hands moved
+    wheel.advance_to(1_234_560);
+    assert_eq!(
+        wheel.next_deadline(),
+        Some(Duration::from_millis(7_200_000 - 1_234_560))
     );
 }
 
+#[test]
+fn test_next_deadline_exact_after_ticking() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    // (armed duration, elapsed before checking), covering each level and
+    // hands stopped partway through their level
+    let cases = [
+        (Duration::from_millis(150), Duration::from_millis(70)),
+        (Duration::from_millis(1_250), Duration::from_millis(330)),
+        (Duration::from_secs(90), Duration::from_millis(61_230)),
+        (Duration::from_secs(7_200), Duration::from_millis(3_725_470)),
+    ];
+
+    for (duration, elapsed) in cases {
+        wheel.clear();
+        wheel.init_timer(duration, &waker).unwrap();
+        clock.advance(elapsed);
+        wheel.tick();
+
+        assert_eq!(
+            wheel.next_deadline(),
+            Some(duration - elapsed),
+            "{duration:?} timer after {elapsed:?}"
+        );
+    }
+}
+
 #[test]
 fn test_next_deadline_prefers_ms_over_s_level() {
     let mut wheel = TimeWheel::new();
@@ -692,7 +1029,8 @@ fn test_next_deadline_all_three_levels() {
 
 #[test]
 fn test_next_deadline_after_ms_level_cleared() {
-    let mut wheel = TimeWheel::new();
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
     let (_, waker) = make_waker();
 
     // Add timer at ms level and s level
@@ -702,7 +1040,7 @@ fn test_next_deadline_after_ms_level_cleared() {
         .unwrap();
 
     // Fire the ms-level timer
-    sleep(Duration::from_millis(60));
+    clock.advance(Duration::from_millis(60));
     wheel.tick();
 
     // Now next_deadline should point to the s-level timer
@@ -749,22 +1087,2764 @@ fn test_next_deadline_multiple_same_bucket() {
 }
 
 #[test]
-fn test_next_deadline_cancelled_timer_still_in_bucket() {
+fn test_next_deadline_ignores_cancelled_timer() {
     let mut wheel = TimeWheel::new();
     let (_, waker) = make_waker();
 
     let id = wheel.init_timer(Duration::from_millis(30), &waker).unwrap();
 
-    // Cancel the timer
+    // Cancelling the only timer of the bucket clears its occupied bit
     wheel.drop(id);
 
-    // next_deadline still sees the occupied bucket (timer is cancelled but bucket bit is set)
-    // This documents current behavior - the occupied bit isn't cleared on cancel
-    let deadline = wheel.next_deadline();
-    // Note: This may return Some even though timer is cancelled, since we don't
-    // clear the bucket occupied bit on cancel
-    assert!(
-        deadline.is_some(),
-        "Bucket occupied bit should still be set after cancel"
+    assert_eq!(wheel.next_deadline(), None);
+}
+
+#[test]
+fn test_next_deadline_cancel_keeps_shared_bucket() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(30), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(35), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+
+    wheel.drop(id);
+
+    // The other timer of the bucket is still pending
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(30)));
+}
+
+#[test]
+fn test_next_deadline_instant_from_last_tick() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+    assert_eq!(wheel.next_deadline_instant(), None);
+
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    clock.advance(Duration::from_millis(200));
+    wheel.tick();
+
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(300)));
+    assert_eq!(wheel.next_deadline_instant(), Some(500));
+
+    // time passing without a tick does not move the deadline
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(wheel.next_deadline_instant(), Some(500));
+}
+
+#[test]
+fn test_cancel_last_timer_of_second_level_bucket() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    let id = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    wheel.drop(id);
+
+    assert_eq!(wheel.next_deadline(), None);
+}
+
+#[test]
+fn test_cancel_hour_level_timers_frees_slots() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    let ids: Vec<_> = (0..1000)
+        .map(|i| {
+            wheel
+                .init_timer(Duration::from_hours(1) + Duration::from_secs(i), &waker)
+                .unwrap()
+        })
+        .collect();
+    for id in ids {
+        wheel.drop(id);
+    }
+
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.stats(), TimeWheelStats::default());
+
+    // every slot was released right away and is reused
+    for _ in 0..1000 {
+        let id = wheel.init_timer(Duration::from_hours(1), &waker).unwrap();
+        assert!(id.index < 1000);
+    }
+}
+
+#[test]
+fn test_classify() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let at = |level, index| Some(BucketLocation { level, index });
+
+    assert_eq!(wheel.classify(Duration::ZERO), None);
+    assert_eq!(wheel.classify(Duration::from_hours(24)), None);
+    assert_eq!(
+        wheel.classify(Duration::from_millis(150)),
+        at(Level::Ms, 15)
+    );
+    assert_eq!(
+        wheel.classify(Duration::from_millis(1_500)),
+        at(Level::Second, 1)
+    );
+    assert_eq!(
+        wheel.classify(Duration::from_secs(100)),
+        at(Level::Minute, 1)
+    );
+    assert_eq!(wheel.classify(Duration::from_hours(5)), at(Level::Hour, 5));
+
+    // measured from the current hands
+    wheel.advance_to(950);
+    assert_eq!(
+        wheel.classify(Duration::from_millis(100)),
+        at(Level::Second, 1)
+    );
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_next_deadline_per_level() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    assert_eq!(wheel.next_deadline_per_level(), (None, None, None, None));
+
+    wheel
+        .init_timer(Duration::from_millis(1_500), &waker)
+        .unwrap();
+    wheel.init_timer(Duration::from_secs(100), &waker).unwrap();
+    wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+    assert_eq!(
+        wheel.next_deadline_per_level(),
+        (
+            None,
+            Some(Duration::from_millis(1_500)),
+            Some(Duration::from_secs(100)),
+            Some(Duration::from_hours(2))
+        )
+    );
+
+    // cascaded down to the ms level once its second bucket is reached
+    wheel.advance_to(1_400);
+    assert_eq!(
+        wheel.next_deadline_per_level(),
+        (
+            Some(Duration::from_millis(100)),
+            None,
+            Some(Duration::from_millis(98_600)),
+            Some(Duration::from_millis(7_198_600))
+        )
+    );
+}
+
+#[test]
+fn test_location_follows_cascade() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id = wheel
+        .init_timer(Duration::from_millis(1_500), &waker)
+        .unwrap();
+    assert_eq!(
+        wheel.location(id),
+        wheel.classify(Duration::from_millis(1_500))
     );
+    assert_eq!(
+        wheel.location(id),
+        Some(BucketLocation {
+            level: Level::Second,
+            index: 1
+        })
+    );
+
+    wheel.advance_to(1_400);
+    assert_eq!(
+        wheel.location(id),
+        Some(BucketLocation {
+            level: Level::Ms,
+            index: 50
+        })
+    );
+    wheel.advance_to(1_510);
+    assert_eq!(wheel.location(id), None);
+}
+
+// ============================================================================
+// len tests
+// ============================================================================
+
+#[test]
+fn test_len_counts_registered_timers() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    assert!(wheel.is_empty());
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+
+    assert_eq!(wheel.len(), 2);
+    assert!(!wheel.is_empty());
+}
+
+#[test]
+fn test_len_decreases_when_timer_fires() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(300), &waker)
+        .unwrap();
+
+    clock.advance(Duration::from_millis(35));
+    wheel.tick();
+
+    assert_eq!(wheel.len(), 1);
+}
+
+#[test]
+fn test_len_decreases_when_timer_dropped() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.drop(id);
+    assert!(wheel.is_empty());
+
+    // reaching the cancelled timer's bucket must not count it twice
+    clock.advance(Duration::from_millis(35));
+    wheel.tick();
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_storage_len_counts_fired_timers_until_dropped() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let fired = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    let cancelled = wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
+    assert_eq!(wheel.storage_len(), 2);
+
+    wheel.advance_to(30);
+    assert_eq!(wheel.len(), 1);
+    assert_eq!(wheel.storage_len(), 2);
+
+    wheel.drop(cancelled);
+    assert_eq!(wheel.storage_len(), 1);
+    wheel.drop(fired);
+    assert_eq!(wheel.storage_len(), 0);
+}
+
+#[test]
+fn test_reap_done_releases_unclaimed_fired_timers() {
+    let mut wheel = TimeWheel::<u32, MockClock>::from_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let fired: Vec<_> = (0..3)
+        .map(|i| {
+            wheel
+                .init_timer_with_payload(Duration::from_millis(20), &waker, i)
+                .unwrap()
+        })
+        .collect();
+    let waiting = wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
+    wheel.advance_to(30);
+    assert_eq!(wheel.unclaimed_fired(), 3);
+
+    wheel.drop(fired[0]);
+    assert_eq!(wheel.unclaimed_fired(), 2);
+
+    assert_eq!(wheel.reap_done(), 2);
+    assert_eq!(wheel.unclaimed_fired(), 0);
+    assert_eq!(wheel.storage_len(), 1);
+    assert_eq!(wheel.take_payload(fired[1]), None);
+    assert_eq!(wheel.poll(fired[2], &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(waiting, &waker), Poll::Pending);
+}
+
+// ============================================================================
+// init_timers tests
+// ============================================================================
+
+#[test]
+fn test_init_timers_returns_ids_in_order() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let durations = [
+        Duration::from_millis(20),
+        Duration::from_millis(300),
+        Duration::from_millis(40),
+    ];
+    let ids = wheel.init_timers(&durations, &waker).unwrap();
+    let indices: Vec<_> = ids.iter().map(|id| id.index).collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+    assert_eq!(wheel.len(), 3);
+
+    clock.advance(Duration::from_millis(55));
+    wheel.tick();
+
+    assert_eq!(counter.count(), 2);
+    assert_eq!(wheel.poll(ids[0], &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(ids[1], &waker), Poll::Pending);
+    assert_eq!(wheel.poll(ids[2], &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_init_timers_rejects_all_on_too_long() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    let durations = [Duration::from_millis(20), Duration::from_hours(25)];
+    let result = wheel.init_timers(&durations, &waker);
+
+    assert_eq!(
+        result,
+        Err(TimerError::DurationTooLong(DurationTooLong {
+            requested: Duration::from_hours(25),
+            max: Duration::from_hours(24),
+        }))
+    );
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.next_deadline(), None);
+}
+
+// ============================================================================
+// payload tests
+// ============================================================================
+
+#[test]
+fn test_take_payload_after_fire() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::<&str, MockClock>::from_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id = wheel
+        .init_timer_with_payload(Duration::from_millis(20), &waker, "conn-1")
+        .unwrap();
+
+    // Not available while the timer is waiting
+    assert_eq!(wheel.take_payload(id), None);
+
+    clock.advance(Duration::from_millis(35));
+    wheel.tick();
+
+    assert_eq!(wheel.take_payload(id), Some("conn-1"));
+    // Taken only once
+    assert_eq!(wheel.take_payload(id), None);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_take_payload_of_cancelled_timer() {
+    let mut wheel = TimeWheel::new_with_payload();
+    let (_, waker) = make_waker();
+
+    let id = wheel
+        .init_timer_with_payload(Duration::from_millis(20), &waker, 7u32)
+        .unwrap();
+    wheel.drop(id);
+
+    assert_eq!(wheel.take_payload(id), None);
+}
+
+#[test]
+fn test_cancel_where_matching_payloads() {
+    let mut wheel = TimeWheel::<_, MockClock>::from_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let durations = [
+        Duration::from_millis(20),
+        Duration::from_millis(1_500),
+        Duration::from_hours(2),
+    ];
+    for (conn, duration) in [1u32, 2]
+        .into_iter()
+        .flat_map(|c| durations.map(|d| (c, d)))
+    {
+        wheel
+            .init_timer_with_payload(duration, &waker, conn)
+            .unwrap();
+    }
+    let fired = wheel
+        .init_timer_with_payload(Duration::from_millis(10), &waker, 1)
+        .unwrap();
+    wheel.advance_to(25);
+    assert_eq!(counter.count(), 1);
+
+    // the fired timer is not cancelled again
+    assert_eq!(wheel.cancel_where(|&conn| conn == 1), 3);
+    assert_eq!(wheel.len(), 3);
+    assert_eq!(wheel.take_payload(fired), Some(1));
+    assert_eq!(wheel.stats().ms_timers, 1);
+    assert_eq!(wheel.stats().occupied_ms_buckets, 1);
+
+    assert_eq!(wheel.cancel_where(|&conn| conn == 1), 0);
+    assert_eq!(wheel.cancel_where(|_| true), 3);
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.stats(), TimeWheelStats::default());
+}
+
+// ============================================================================
+// with_capacity tests
+// ============================================================================
+
+#[test]
+fn test_with_capacity_behaves_like_new() {
+    assert!(TimeWheel::with_capacity(1000).capacity() >= 1000);
+
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    wheel.reserve(1000);
+    let (counter, waker) = make_waker();
+
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.next_deadline(), None);
+
+    let ids: Vec<_> = (0..1000)
+        .map(|i| {
+            wheel
+                .init_timer(Duration::from_millis(i % 40 + 1), &waker)
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(ids[0].index, 0);
+    assert_eq!(wheel.len(), 1000);
+
+    clock.advance(Duration::from_millis(60));
+    wheel.tick();
+
+    assert_eq!(counter.count(), 1000);
+    assert!(wheel.is_empty());
+}
+
+// ============================================================================
+// advance_to tests
+// ============================================================================
+
+#[test]
+fn test_advance_to_fires_without_sleeping() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    wheel.advance_to(15);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(35);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_advance_to_fast_forwards_second_level() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+
+    wheel.advance_to(6_000);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_advance_to_past_instant_is_ignored() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    wheel.advance_to(15);
+    wheel.advance_to(0);
+    assert_eq!(counter.count(), 0);
+
+    // Going back did not rewind the wheel
+    wheel.advance_to(35);
+    assert_eq!(counter.count(), 1);
+}
+
+struct JumpingClock(Rc<Cell<u64>>);
+
+impl Clock for JumpingClock {
+    type Instant = u64;
+
+    fn now(&self) -> u64 {
+        self.0.get()
+    }
+
+    fn duration_between(earlier: u64, later: u64) -> Duration {
+        Duration::from_millis(later.saturating_sub(earlier))
+    }
+
+    fn add(instant: u64, duration: Duration) -> u64 {
+        instant + duration.as_millis() as u64
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration.as_millis() as u64);
+    }
+}
+
+#[test]
+fn test_tick_with_clock_jumping_backwards() {
+    let now = Rc::new(Cell::new(1_000));
+    let mut wheel = TimeWheel::new_with_clock(JumpingClock(now.clone()));
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+
+    now.set(1_030);
+    assert_eq!(wheel.tick(), 0);
+    now.set(500);
+    assert_eq!(wheel.tick(), 0);
+    assert_eq!(counter.count(), 0);
+
+    // the jump back neither rewound the wheel nor made it replay time
+    now.set(1_050);
+    assert_eq!(wheel.tick(), 0);
+    now.set(1_060);
+    assert_eq!(wheel.tick(), 1);
+}
+
+fn assert_gap_fires_each_timer_once(gap: Duration) {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    let gap_ms = gap.as_millis() as u64;
+
+    // timers spread up to twice the gap so some are left for after it
+    let armed: Vec<_> = (1..=200)
+        .map(|i| {
+            let duration = Duration::from_millis(i * gap_ms / 100);
+            (wheel.init_timer(duration, &waker).unwrap(), duration)
+        })
+        .collect();
+
+    let mut fired = wheel.advance_to_collect(gap_ms);
+    let due_in_gap = armed
+        .iter()
+        // a timer fires on the tick after the one its duration falls in
+        .filter(|(_, duration)| (duration.as_millis() as u64 / 10 + 1) * 10 <= gap_ms)
+        .count();
+    assert_eq!(fired.len(), due_in_gap, "{gap:?} gap");
+
+    fired.extend(wheel.advance_to_collect(3 * gap_ms));
+    fired.sort();
+    let mut expected: Vec<_> = armed.iter().map(|&(id, _)| id).collect();
+    expected.sort();
+    assert_eq!(fired, expected, "{gap:?} gap");
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_gap_longer_than_ms_level() {
+    assert_gap_fires_each_timer_once(Duration::from_millis(150));
+    assert_gap_fires_each_timer_once(Duration::from_millis(450));
+}
+
+#[test]
+fn test_gap_longer_than_second_bucket_rotations() {
+    assert_gap_fires_each_timer_once(Duration::from_millis(1_300));
+}
+
+#[test]
+fn test_gap_longer_than_second_level() {
+    assert_gap_fires_each_timer_once(Duration::from_secs(65));
+}
+
+// ============================================================================
+// tick_collect tests
+// ============================================================================
+
+#[test]
+fn test_advance_to_collect_returns_fired_ids() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id1 = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    let id2 = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    let id3 = wheel.init_timer(Duration::from_millis(30), &waker).unwrap();
+    let _id4 = wheel.init_timer(Duration::from_millis(90), &waker).unwrap();
+
+    // Cancelled timers are not reported
+    wheel.drop(id2);
+
+    let fired = wheel.advance_to_collect(45);
+    assert_eq!(fired, vec![id1, id3]);
+
+    let fired = wheel.advance_to_collect(50);
+    assert!(fired.is_empty());
+}
+
+#[test]
+fn test_tick_collect_returns_fired_ids() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    clock.advance(Duration::from_millis(35));
+    assert_eq!(wheel.tick_collect(), vec![id]);
+}
+
+// ============================================================================
+// cascade precision tests
+// ============================================================================
+
+#[test]
+fn test_second_level_keeps_sub_second_offset() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter_1000, waker_1000) = make_waker();
+    let (counter_1500, waker_1500) = make_waker();
+
+    wheel
+        .init_timer(Duration::from_millis(1000), &waker_1000)
+        .unwrap();
+    wheel
+        .init_timer(Duration::from_millis(1500), &waker_1500)
+        .unwrap();
+
+    wheel.advance_to(990);
+    assert_eq!(counter_1000.count(), 0, "1000ms timer fired early");
+
+    wheel.advance_to(1_050);
+    assert_eq!(counter_1000.count(), 1);
+    assert_eq!(
+        counter_1500.count(),
+        0,
+        "1500ms timer fired with the 1000ms one"
+    );
+
+    wheel.advance_to(1_490);
+    assert_eq!(counter_1500.count(), 0, "1500ms timer fired early");
+
+    wheel.advance_to(1_550);
+    assert_eq!(counter_1500.count(), 1);
+}
+
+#[test]
+fn test_long_timer_does_not_fire_early() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel
+        .init_timer(Duration::from_secs(2 * 3600), &waker)
+        .unwrap();
+
+    wheel.advance_to(7_199_000);
+    assert_eq!(counter.count(), 0);
+    assert!(wheel.next_deadline().unwrap() <= Duration::from_secs(1));
+
+    wheel.advance_to(7_201_000);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_second_level_timers_fire_at_distinct_times() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id_1100 = wheel
+        .init_timer(Duration::from_millis(1100), &waker)
+        .unwrap();
+    let id_1500 = wheel
+        .init_timer(Duration::from_millis(1500), &waker)
+        .unwrap();
+    let id_1900 = wheel
+        .init_timer(Duration::from_millis(1900), &waker)
+        .unwrap();
+
+    let mut fired_at = Vec::new();
+    for ms in (0..=2000).step_by(10) {
+        for id in wheel.advance_to_collect(ms) {
+            fired_at.push((id, ms));
+        }
+    }
+
+    assert_eq!(
+        fired_at,
+        vec![(id_1100, 1110), (id_1500, 1510), (id_1900, 1910)]
+    );
+}
+
+#[test]
+fn test_last_hours_keep_sub_hour_precision() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let ms = |h: u64, m: u64| (h * 60 + m) * 60_000;
+    let h23 = wheel
+        .init_timer(Duration::from_millis(ms(23, 0)), &waker)
+        .unwrap();
+    let h23m30 = wheel
+        .init_timer(Duration::from_millis(ms(23, 30)), &waker)
+        .unwrap();
+    let h23m59 = wheel
+        .init_timer(Duration::from_millis(ms(23, 59)), &waker)
+        .unwrap();
+
+    for (id, at) in [(h23, ms(23, 0)), (h23m30, ms(23, 30)), (h23m59, ms(23, 59))] {
+        assert_eq!(wheel.advance_to_collect(at), vec![]);
+        assert_eq!(wheel.advance_to_collect(at + 10), vec![id]);
+    }
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_long_timers_get_their_own_hour_bucket() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    for hours in [2, 20, 23] {
+        let id = wheel
+            .init_timer(Duration::from_hours(hours), &waker)
+            .unwrap();
+        assert_eq!(
+            wheel.location(id),
+            Some(BucketLocation {
+                level: Level::Hour,
+                index: hours as usize
+            })
+        );
+    }
+
+    // late in the day, the next day's early hours wrap to the buckets behind the hand
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    wheel.advance_to(ms_of(22, 30));
+    let id = wheel.init_timer(Duration::from_hours(3), &waker).unwrap();
+    assert_eq!(
+        wheel.location(id),
+        Some(BucketLocation {
+            level: Level::Hour,
+            index: 1
+        })
+    );
+    assert_eq!(wheel.advance_to_collect(ms_of(25, 30)), vec![]);
+    assert_eq!(wheel.advance_to_collect(ms_of(25, 30) + 10), vec![id]);
+}
+
+#[test]
+fn test_short_tick_spans_several_hour_rotations() {
+    let mut wheel = TimeWheel::<(), MockClock>::from_clock(MockClock::new())
+        .with_tick(Duration::from_millis(1));
+    let (_, waker) = make_waker();
+
+    // the hour level spans 2.4h at a 1ms tick
+    let id = wheel.init_timer(Duration::from_hours(10), &waker).unwrap();
+    assert_eq!(wheel.advance_to_collect(ms_of(10, 0) - 1), vec![]);
+    assert_eq!(wheel.advance_to_collect(ms_of(10, 0) + 1), vec![id]);
+}
+
+fn ms_of(hours: u64, minutes: u64) -> u64 {
+    (hours * 60 + minutes) * 60_000
+}
+
+#[test]
+fn test_catch_up_fires_timers_across_cascade_boundary() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id_95 = wheel.init_timer(Duration::from_millis(95), &waker).unwrap();
+    let id_105 = wheel
+        .init_timer(Duration::from_millis(105), &waker)
+        .unwrap();
+    // straddling the first cascade from the second level
+    let id_995 = wheel
+        .init_timer(Duration::from_millis(995), &waker)
+        .unwrap();
+    let id_1005 = wheel
+        .init_timer(Duration::from_millis(1_005), &waker)
+        .unwrap();
+
+    assert_eq!(wheel.advance_to_collect(120), vec![id_95, id_105]);
+    assert_eq!(wheel.advance_to_collect(1_050), vec![id_995, id_1005]);
+    assert_eq!(counter.count(), 4);
+}
+
+// ============================================================================
+// fired count tests
+// ============================================================================
+
+#[test]
+fn test_advance_to_returns_fired_count() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(15), &waker).unwrap();
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(90), &waker).unwrap();
+    wheel.drop(id);
+
+    assert_eq!(wheel.advance_to(5), 0);
+    assert_eq!(wheel.advance_to(45), 2);
+    assert_eq!(wheel.advance_to(50), 0);
+}
+
+// ============================================================================
+// max_timers tests
+// ============================================================================
+
+#[test]
+fn test_max_timers_rejects_past_the_cap() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_max_timers(2);
+    let (_, waker) = make_waker();
+
+    let first = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    assert_eq!(
+        wheel.init_timer(Duration::from_secs(5), &waker),
+        Err(TimerError::TooManyTimers)
+    );
+    assert_eq!(wheel.len(), 2);
+
+    // a fired timer no longer counts
+    wheel.advance_to(25);
+    assert_eq!(wheel.poll(first, &waker), Poll::Ready(()));
+    assert!(wheel.init_timer(Duration::from_secs(5), &waker).is_ok());
+}
+
+#[test]
+fn test_try_init_timer_does_not_clone_on_rejection() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_max_timers(1);
+    let (counter, waker) = make_waker();
+
+    wheel
+        .try_init_timer(Duration::from_millis(50), &waker)
+        .unwrap();
+    // the waker held by the armed timer
+    assert_eq!(Arc::strong_count(&counter), 3);
+
+    assert_eq!(
+        wheel.try_init_timer(Duration::from_millis(50), &waker),
+        Err(TimerError::TooManyTimers)
+    );
+    assert!(matches!(
+        wheel.try_init_timer(Duration::from_hours(25), &waker),
+        Err(TimerError::DurationTooLong(_))
+    ));
+    assert_eq!(Arc::strong_count(&counter), 3);
+    assert_eq!(wheel.storage_len(), 1);
+}
+
+#[test]
+fn test_max_timers_batch_is_all_or_nothing() {
+    let mut wheel = TimeWheel::new().with_max_timers(3);
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
+    let durations = [Duration::from_secs(1); 3];
+    assert_eq!(
+        wheel.init_timers(&durations, &waker),
+        Err(TimerError::TooManyTimers)
+    );
+    assert_eq!(wheel.len(), 1);
+    assert!(wheel.init_timers(&durations[..2], &waker).is_ok());
+}
+
+#[test]
+fn test_fixed_capacity_never_grows() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_fixed_capacity(4);
+    let (_, waker) = make_waker();
+    let capacity = wheel.capacity();
+    assert!(capacity >= 4);
+
+    let ids: Vec<_> = (1..=4)
+        .map(|i| {
+            wheel
+                .init_timer(Duration::from_millis(i * 20), &waker)
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        wheel.init_timer(Duration::from_secs(1), &waker),
+        Err(TimerError::TooManyTimers)
+    );
+
+    // fired timers keep their slot until dropped
+    wheel.advance_to(100);
+    assert!(wheel.is_empty());
+    assert_eq!(
+        wheel.init_timer(Duration::from_secs(1), &waker),
+        Err(TimerError::TooManyTimers)
+    );
+
+    for id in &ids[..2] {
+        wheel.drop(*id);
+    }
+    let reused = wheel
+        .init_timers(&[Duration::from_secs(1); 2], &waker)
+        .unwrap();
+    assert!(reused.iter().all(|id| id.index < 2));
+
+    wheel.shrink_to_fit();
+    assert_eq!(wheel.capacity(), capacity);
+}
+
+// ============================================================================
+// max_fires_per_tick tests
+// ============================================================================
+
+#[test]
+fn test_max_fires_per_tick_spreads_a_burst() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone()).with_max_fires_per_tick(10);
+    let (counter, waker) = make_waker();
+
+    let armed: Vec<_> = (0..25)
+        .map(|_| wheel.init_timer(Duration::from_millis(50), &waker).unwrap())
+        .collect();
+
+    clock.advance(Duration::from_millis(60));
+    assert_eq!(
+        wheel.tick_bounded(),
+        TickOutcome {
+            fired: 10,
+            more: true
+        }
+    );
+    assert_eq!(counter.count(), 10);
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(10)));
+
+    // the rest fire in arming order on the following calls
+    assert_eq!(wheel.tick_collect(), armed[10..20]);
+    assert_eq!(
+        wheel.tick_bounded(),
+        TickOutcome {
+            fired: 5,
+            more: false
+        }
+    );
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_max_fires_per_tick_keeps_later_deadlines() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone()).with_max_fires_per_tick(1);
+    let (_, waker) = make_waker();
+
+    let first = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let second = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    let third = wheel
+        .init_timer(Duration::from_millis(900), &waker)
+        .unwrap();
+
+    // the time not processed by a capped call is not lost
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(wheel.tick_collect(), vec![first]);
+    assert_eq!(wheel.tick_collect(), vec![second]);
+    assert_eq!(
+        wheel.tick_bounded(),
+        TickOutcome {
+            fired: 1,
+            more: false
+        }
+    );
+    assert_eq!(wheel.poll(third, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_fires_per_tick_unlimited_by_default() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    for _ in 0..1000 {
+        wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    }
+
+    clock.advance(Duration::from_millis(60));
+    assert_eq!(
+        wheel.tick_bounded(),
+        TickOutcome {
+            fired: 1000,
+            more: false
+        }
+    );
+}
+
+// ============================================================================
+// tick_expired tests
+// ============================================================================
+
+#[test]
+fn test_tick_expired_yields_fired_timers_with_payloads() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::<&str, MockClock>::from_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let b = wheel
+        .init_timer_with_payload(Duration::from_millis(40), &waker, "b")
+        .unwrap();
+    let a = wheel
+        .init_timer_with_payload(Duration::from_millis(20), &waker, "a")
+        .unwrap();
+    wheel
+        .init_timer_with_payload(Duration::from_secs(1), &waker, "later")
+        .unwrap();
+
+    clock.advance(Duration::from_millis(100));
+    let expired: Vec<_> = wheel.tick_expired().collect();
+    assert_eq!(expired, vec![(a, Some("a")), (b, Some("b"))]);
+    assert_eq!(counter.count(), 2);
+    assert!(wheel.fired_last_tick());
+    assert_eq!(wheel.poll(a, &waker), Poll::Ready(()));
+
+    // each timer is yielded once
+    assert_eq!(wheel.tick_expired().count(), 0);
+    assert!(!wheel.fired_last_tick());
+    assert_eq!(wheel.len(), 1);
+}
+
+#[test]
+fn test_tick_expired_is_lazy() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let armed: Vec<_> = (1..=3)
+        .map(|i| {
+            wheel
+                .init_timer(Duration::from_millis(i * 100), &waker)
+                .unwrap()
+        })
+        .collect();
+
+    clock.advance(Duration::from_millis(500));
+    assert_eq!(wheel.tick_expired().next(), Some((armed[0], Some(()))));
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.len(), 2);
+
+    // the timers not consumed fire on the next tick
+    assert_eq!(wheel.tick_collect(), armed[1..]);
+}
+
+// ============================================================================
+// shrink_to_fit tests
+// ============================================================================
+
+#[test]
+fn test_shrink_to_fit_releases_a_burst() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let keep = wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    let ids: Vec<_> = (0..10_000)
+        .map(|_| wheel.init_timer(Duration::from_millis(50), &waker).unwrap())
+        .collect();
+    assert!(wheel.capacity() >= 10_001);
+
+    wheel.advance_to(60);
+    for id in &ids {
+        wheel.drop(*id);
+    }
+    wheel.shrink_to_fit();
+    assert!(wheel.capacity() < 10_000);
+
+    // waiting timers and stale ids are unaffected
+    assert_eq!(wheel.poll(ids[0], &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(keep, &waker), Poll::Pending);
+    assert_eq!(wheel.advance_to_collect(5010), vec![keep]);
+}
+
+// ============================================================================
+// drain_all tests
+// ============================================================================
+
+#[test]
+fn test_drain_all_cancels_every_timer() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let fired = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    let id_ms = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let id_s = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    let id_h = wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+
+    wheel.advance_to(25);
+    assert_eq!(counter.count(), 1);
+    wheel.pause();
+
+    let mut ids = wheel.drain_all();
+    ids.sort();
+    assert_eq!(ids, vec![id_ms, id_s, id_h]);
+
+    assert!(wheel.is_empty());
+    assert!(!wheel.is_paused());
+    assert_eq!(wheel.next_deadline(), None);
+    // The fired slot was released as well
+    assert_eq!(wheel.take_payload(fired), None);
+
+    wheel.advance_to(1_000);
+    assert_eq!(counter.count(), 1);
+
+    // Slots are reused from the start
+    let id = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    assert_eq!(id.index, 0);
+}
+
+// ============================================================================
+// clear tests
+// ============================================================================
+
+#[test]
+fn test_clear_resets_wheel() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+    clock.advance(Duration::from_millis(250));
+    wheel.tick();
+    assert_eq!(counter.count(), 1);
+
+    wheel.clear();
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.next_deadline(), None);
+    assert_eq!(wheel.stats(), TimeWheelStats::default());
+
+    // Slots are reused from the start
+    let id = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    assert_eq!(id.index, 0);
+
+    // the last tick was reset to now, the elapsed 250ms are not replayed
+    assert_eq!(wheel.tick(), 0);
+    clock.advance(Duration::from_hours(3));
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 2);
+}
+
+// ============================================================================
+// stats tests
+// ============================================================================
+
+#[test]
+fn test_stats_empty_wheel() {
+    let wheel = TimeWheel::new();
+    assert_eq!(wheel.stats(), TimeWheelStats::default());
+}
+
+#[test]
+fn test_stats_counts_timers_per_level() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(25), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(30), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(90), &waker).unwrap();
+    wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+    wheel.init_timer(Duration::from_hours(3), &waker).unwrap();
+
+    assert_eq!(
+        wheel.stats(),
+        TimeWheelStats {
+            ms_timers: 3,
+            s_timers: 1,
+            m_timers: 1,
+            h_timers: 2,
+            occupied_ms_buckets: 2,
+            occupied_s_buckets: 1,
+            occupied_m_buckets: 1,
+            occupied_h_buckets: 2,
+        }
+    );
+}
+
+#[test]
+fn test_occupied_buckets() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+    assert_eq!(wheel.occupied_buckets(), (0, 0, 0, 0));
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(25), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    wheel.init_timer(Duration::from_secs(9), &waker).unwrap();
+    wheel.init_timer(Duration::from_mins(5), &waker).unwrap();
+    wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+
+    assert_eq!(wheel.occupied_buckets(), (2, 2, 1, 1));
+}
+
+// ============================================================================
+// peek_next tests
+// ============================================================================
+
+#[test]
+fn test_peek_next_empty() {
+    let wheel = TimeWheel::new();
+    assert_eq!(wheel.peek_next(), None);
+}
+
+#[test]
+fn test_peek_next_returns_soonest_timer() {
+    let mut wheel = TimeWheel::new();
+    let (counter, waker) = make_waker();
+
+    // Both land in the same second-level bucket
+    wheel
+        .init_timer(Duration::from_millis(1210), &waker)
+        .unwrap();
+    let id = wheel
+        .init_timer(Duration::from_millis(1200), &waker)
+        .unwrap();
+    wheel.init_timer(Duration::from_hours(1), &waker).unwrap();
+
+    assert_eq!(wheel.peek_next(), Some((id, Duration::from_millis(1200))));
+    assert_eq!(wheel.peek_next().map(|(_, d)| d), wheel.next_deadline());
+
+    // Peeking does not fire nor remove anything
+    assert_eq!(wheel.len(), 3);
+    assert_eq!(counter.count(), 0);
+}
+
+// ============================================================================
+// early firing tests
+// ============================================================================
+
+#[test]
+fn test_timer_armed_on_lagging_wheel_does_not_fire_early() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    // the wheel was last ticked at 0 and is 50ms behind the clock
+    clock.advance(Duration::from_millis(50));
+    wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(300), &waker)
+        .unwrap();
+
+    assert_eq!(wheel.advance_to(50), 0);
+    assert_eq!(wheel.advance_to(60), 1);
+
+    assert_eq!(wheel.advance_to(340), 0);
+    assert_eq!(wheel.advance_to(350), 1);
+    assert_eq!(counter.count(), 2);
+}
+
+#[test]
+fn test_no_timer_fires_before_its_duration() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    // arm timers while the wheel is ticked irregularly and check each against its own target
+    let mut due = Vec::new();
+    for step in 0..200u64 {
+        clock.advance(Duration::from_millis(7 + step % 13));
+        if step % 3 == 0 {
+            let duration = Duration::from_millis(step * 37 % 2_000);
+            let id = wheel.init_timer(duration, &waker).unwrap();
+            due.push((id, clock.now() + duration.as_millis() as u64));
+        }
+        if step % 5 == 0 {
+            let now = clock.now();
+            for id in wheel.tick_collect() {
+                let &(_, target) = due.iter().find(|(due_id, _)| *due_id == id).unwrap();
+                assert!(target <= now, "{id:?} due at {target} fired at {now}");
+            }
+        }
+    }
+}
+
+// ============================================================================
+// rounding tests
+// ============================================================================
+
+fn fire_time(rounding: RoundingPolicy, duration: Duration) -> u64 {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_rounding(rounding);
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(duration, &waker).unwrap();
+    (0..=100)
+        .map(|tick| tick * 10)
+        .find(|&ms| wheel.advance_to(ms) > 0)
+        .unwrap()
+}
+
+#[test]
+fn test_rounding_floor_is_default() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    wheel.init_timer(Duration::from_millis(19), &waker).unwrap();
+
+    assert_eq!(wheel.advance_to(20), 1);
+    assert_eq!(
+        fire_time(RoundingPolicy::Floor, Duration::from_millis(19)),
+        20
+    );
+}
+
+#[test]
+fn test_rounding_ceil_waits_for_the_next_tick() {
+    assert_eq!(
+        fire_time(RoundingPolicy::Ceil, Duration::from_millis(19)),
+        30
+    );
+    assert_eq!(
+        fire_time(RoundingPolicy::Ceil, Duration::from_millis(11)),
+        30
+    );
+    // whole ticks are not rounded further
+    assert_eq!(
+        fire_time(RoundingPolicy::Ceil, Duration::from_millis(20)),
+        30
+    );
+}
+
+#[test]
+fn test_rounding_nearest() {
+    assert_eq!(
+        fire_time(RoundingPolicy::Nearest, Duration::from_millis(14)),
+        20
+    );
+    assert_eq!(
+        fire_time(RoundingPolicy::Nearest, Duration::from_millis(15)),
+        30
+    );
+    assert_eq!(
+        fire_time(RoundingPolicy::Nearest, Duration::from_millis(19)),
+        30
+    );
+}
+
+// ============================================================================
+// custom tick tests
+// ============================================================================
+
+#[test]
+fn test_new_with_tick_fires_with_finer_resolution() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_tick(Duration::from_millis(1));
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(3), &waker).unwrap();
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(3)));
+
+    wheel.advance_to(3);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(4);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_new_with_tick_cascades_second_level() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_tick(Duration::from_millis(1));
+    let (counter, waker) = make_waker();
+
+    // 1ms ticks make the ms level span 20ms
+    wheel.init_timer(Duration::from_millis(55), &waker).unwrap();
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(55)));
+
+    wheel.advance_to(55);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(56);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+#[should_panic]
+fn test_new_with_tick_rejects_sub_millisecond_tick() {
+    let _ = TimeWheel::new_with_tick(Duration::from_micros(100));
+}
+
+#[test]
+fn test_tick_duration() {
+    assert_eq!(TimeWheel::new().tick_duration(), Duration::from_millis(10));
+    assert_eq!(
+        TimeWheel::new_with_tick(Duration::from_millis(1)).tick_duration(),
+        Duration::from_millis(1)
+    );
+}
+
+#[test]
+fn test_limits_follow_the_tick() {
+    let wheel = TimeWheel::new();
+    assert_eq!(wheel.max_duration(), Duration::from_hours(24));
+    assert_eq!(wheel.ms_level_span(), Duration::from_secs(1));
+    assert_eq!(wheel.s_level_span(), Duration::from_mins(1));
+    assert_eq!(wheel.m_level_span(), Duration::from_hours(1));
+
+    let wheel = TimeWheel::new_with_tick(Duration::from_millis(1));
+    assert_eq!(wheel.ms_level_span(), Duration::from_millis(100));
+    assert_eq!(wheel.s_level_span(), Duration::from_secs(6));
+    assert_eq!(wheel.m_level_span(), Duration::from_mins(6));
+    assert_eq!(wheel.max_duration(), Duration::from_hours(24));
+
+    // the bound is exclusive
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+    assert!(wheel.init_timer(wheel.max_duration(), &waker).is_err());
+    let longest = wheel.max_duration() - Duration::from_millis(1);
+    assert!(wheel.init_timer(longest, &waker).is_ok());
+}
+
+#[test]
+fn test_current_tick_indices_follow_the_hands() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    assert_eq!(wheel.current_tick_indices(), (0, 0, 0, 0));
+
+    wheel.advance_to(150);
+    assert_eq!(wheel.current_tick_indices(), (15, 0, 0, 0));
+
+    // ms buckets span 10ms, then a second, a minute and an hour
+    wheel.advance_to(7_530_150);
+    assert_eq!(wheel.current_tick_indices(), (15, 30, 5, 2));
+}
+
+#[test]
+fn test_idle_wheel_jumps_to_the_new_position() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+
+    // a full rotation of the hour level spans 24h
+    wheel.advance_to(3 * 86_400_000 + 7_530_150);
+    assert_eq!(wheel.current_tick_indices(), (15, 30, 5, 2));
+    assert_eq!(wheel.next_deadline(), None);
+}
+
+#[test]
+fn test_skipping_idle_ticks_keeps_deadlines() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let ms = wheel
+        .init_timer(Duration::from_millis(150), &waker)
+        .unwrap();
+    let s = wheel.init_timer(Duration::from_secs(30), &waker).unwrap();
+    let h = wheel.init_timer(Duration::from_hours(5), &waker).unwrap();
+
+    // each big step must stop right at the buckets holding the timers
+    assert_eq!(wheel.advance_to_collect(150), vec![]);
+    assert_eq!(wheel.advance_to_collect(160), vec![ms]);
+    assert_eq!(wheel.advance_to_collect(30_000), vec![]);
+    assert_eq!(wheel.advance_to_collect(30_010), vec![s]);
+    assert_eq!(wheel.advance_to_collect(18_000_000), vec![]);
+    assert_eq!(wheel.advance_to_collect(18_000_010), vec![h]);
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_restore_position_rewinds_the_hands() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let soon = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    let later = wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    let start = wheel.position();
+
+    wheel.advance_to(4_000);
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(1000)));
+    assert_eq!(wheel.poll(soon, &waker), Poll::Ready(()));
+
+    wheel.restore_position(start);
+    assert_eq!(wheel.position(), start);
+    assert_eq!(wheel.current_tick_indices(), (0, 0, 0, 0));
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_secs(5)));
+
+    // the remaining timer replays from the restored hands
+    assert_eq!(wheel.advance_to_collect(5_000), vec![]);
+    assert_eq!(wheel.advance_to_collect(5_010), vec![later]);
+}
+
+#[test]
+fn test_display_renders_bucket_occupancy() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    assert_eq!(wheel.to_string(), "ms[] s[] m[] h[]");
+
+    for ms in [20, 20, 20, 50, 10_000, 10_500, 120_000, 7_200_000] {
+        wheel.init_timer(Duration::from_millis(ms), &waker).unwrap();
+    }
+    assert_eq!(wheel.to_string(), "ms[2:3, 5:1] s[10:2] m[2:1] h[2:1]");
+
+    let debug = format!("{wheel:?}");
+    assert!(debug.contains("hands: (0, 0, 0, 0)"), "{debug}");
+    assert!(
+        debug.contains("buckets: ms[2:3, 5:1] s[10:2] m[2:1] h[2:1]"),
+        "{debug}"
+    );
+}
+
+#[test]
+fn test_absorb_moves_waiting_timers() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let mut other = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+    let (other_counter, other_waker) = make_waker();
+
+    let own = wheel
+        .init_timer(Duration::from_millis(100), &waker)
+        .unwrap();
+    let fired = other
+        .init_timer(Duration::from_millis(20), &other_waker)
+        .unwrap();
+    let one_shot = other
+        .init_timer(Duration::from_millis(500), &other_waker)
+        .unwrap();
+    let interval = other
+        .init_interval(Duration::from_secs(1), &other_waker)
+        .unwrap();
+    other.advance_to(300);
+    assert_eq!(other_counter.count(), 1);
+
+    // measured from the hands of the absorbed wheel: 200ms and 700ms left
+    let mut remapped = wheel.absorb(other).unwrap();
+    remapped.sort();
+    assert_eq!(remapped.len(), 2);
+    assert!(remapped.iter().all(|(old, _)| *old != fired));
+    let new_id = |old| remapped.iter().find(|(id, _)| *id == old).unwrap().1;
+    assert_eq!(wheel.len(), 3);
+
+    assert_eq!(wheel.advance_to_collect(110), vec![own]);
+    assert_eq!(wheel.advance_to_collect(200), vec![]);
+    assert_eq!(wheel.advance_to_collect(210), vec![new_id(one_shot)]);
+    assert_eq!(wheel.advance_to_collect(710), vec![new_id(interval)]);
+    assert_eq!(wheel.advance_to_collect(1710), vec![new_id(interval)]);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(other_counter.count(), 4);
+}
+
+#[test]
+fn test_absorb_is_all_or_nothing() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new()).with_max_timers(2);
+    let mut other = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
+    other
+        .init_timers(&[Duration::from_secs(1); 2], &waker)
+        .unwrap();
+
+    assert_eq!(wheel.absorb(other), Err(TimerError::TooManyTimers));
+    assert_eq!(wheel.len(), 1);
+}
+
+#[test]
+fn test_custom_bucket_size() {
+    let mut wheel = TimeWheel::<(), MockClock, 32>::from_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let armed: Vec<_> = (0..40)
+        .map(|_| wheel.init_timer(Duration::from_millis(50), &waker).unwrap())
+        .collect();
+
+    assert_eq!(wheel.advance_to_collect(60), armed);
+    assert_eq!(counter.count(), 40);
+}
+
+#[test]
+fn test_from_clock_with_payload() {
+    let mut wheel = TimeWheel::<&str, MockClock>::from_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id = wheel
+        .init_timer_with_payload(Duration::from_millis(20), &waker, "conn-1")
+        .unwrap();
+    wheel.advance_to(30);
+    assert_eq!(wheel.take_payload(id), Some("conn-1"));
+}
+
+// ============================================================================
+// clock tests
+// ============================================================================
+
+#[test]
+fn test_mock_clock_drives_tick() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    clock.advance(Duration::from_millis(20));
+    assert_eq!(wheel.tick(), 0);
+
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_tick_keeps_sub_tick_remainder() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_secs(1), &waker).unwrap();
+    while counter.count() == 0 {
+        clock.advance(Duration::from_millis(15));
+        wheel.tick();
+    }
+    // fired on the first 15ms step past its tick, the 5ms of each step were not dropped
+    assert!((1_010..1_025).contains(&clock.now()), "{}", clock.now());
+}
+
+#[test]
+fn test_mock_clock_advance_to() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+
+    wheel.advance_to(500);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(510);
+    assert_eq!(counter.count(), 1);
+}
+
+// without std the default wheel reads a MockClock and is driven through advance_to
+#[cfg(not(feature = "std"))]
+#[test]
+fn test_default_wheel_without_std() {
+    let mut wheel = TimeWheel::new();
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    wheel.advance_to(20);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(30);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_tick_clock_counts_wheel_ticks() {
+    let clock = TickClock::<10>::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    // same firing tick as a 50ms timer on a millisecond clock
+    wheel.init_timer_ticks(5, &waker).unwrap();
+    clock.advance_ticks(5);
+    assert_eq!(wheel.tick(), 0);
+    clock.advance_ticks(1);
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_tick_clock_across_levels() {
+    let clock = TickClock::<10>::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer_ticks(12_345, &waker).unwrap();
+    wheel.advance_to(12_345);
+    assert_eq!(counter.count(), 0);
+    assert_eq!(wheel.advance_to_collect(12_346), vec![id]);
+}
+
+#[test]
+fn test_set_last_tick_rebases_without_firing() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+    assert_eq!(wheel.last_tick(), 0);
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.set_last_tick(1_000);
+    assert_eq!(wheel.last_tick(), 1_000);
+
+    // the skipped second is not processed, the timer keeps its tick from the new reference
+    wheel.advance_to(1_000);
+    assert_eq!(counter.count(), 0);
+    wheel.advance_to(1_050);
+    assert_eq!(counter.count(), 0);
+    wheel.advance_to(1_060);
+    assert_eq!(counter.count(), 1);
+}
+
+// ============================================================================
+// remaining tests
+// ============================================================================
+
+#[test]
+fn test_remaining_per_timer() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id_ms = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let id_s = wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    let id_h = wheel.init_timer(Duration::from_hours(2), &waker).unwrap();
+
+    clock.advance(Duration::from_millis(30));
+    wheel.tick();
+
+    assert_eq!(wheel.remaining(id_ms), Some(Duration::from_millis(20)));
+    assert_eq!(wheel.remaining(id_s), Some(Duration::from_millis(4_970)));
+    assert_eq!(
+        wheel.remaining(id_h),
+        Some(Duration::from_hours(2) - Duration::from_millis(30))
+    );
+}
+
+#[test]
+fn test_remaining_none_once_fired_or_cancelled() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let fired = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    let cancelled = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.drop(cancelled);
+    clock.advance(Duration::from_millis(20));
+    wheel.tick();
+
+    assert_eq!(wheel.remaining(fired), None);
+    assert_eq!(wheel.remaining(cancelled), None);
+    let unknown = TimerId {
+        index: 42,
+        generation: 0,
+    };
+    assert_eq!(wheel.remaining(unknown), None);
+}
+
+#[test]
+fn test_poll_at_reached_target_is_ready_before_tick() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert_eq!(wheel.poll_at(id, &waker, 30), Poll::Pending);
+    assert_eq!(wheel.poll_at(id, &waker, 50), Poll::Ready(()));
+    assert_eq!(wheel.state(id), Some(TimerState::Fired));
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.next_deadline(), None);
+
+    // completed by the poll, the wheel has nothing left to wake
+    wheel.advance_to(100);
+    assert_eq!(counter.count(), 0);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_poll_uses_wheel_clock() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    clock.advance(Duration::from_millis(55));
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn test_poll_many_keeps_input_order() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, first) = make_waker();
+    let (counter, second) = make_waker();
+
+    let late = wheel
+        .init_timer(Duration::from_millis(500), &first)
+        .unwrap();
+    let early = wheel.init_timer(Duration::from_millis(50), &first).unwrap();
+    let done = wheel.init_timer(Duration::ZERO, &first).unwrap();
+    wheel.advance_to(60);
+
+    let mut out = Vec::new();
+    wheel.poll_many(&[late, done, early], &second, &mut out);
+    assert_eq!(
+        out,
+        vec![
+            (late, Poll::Pending),
+            (done, Poll::Ready(())),
+            (early, Poll::Ready(())),
+        ]
+    );
+
+    // the pending timer now wakes the new waker
+    wheel.advance_to(510);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_fired_last_tick() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+    assert!(!wheel.fired_last_tick());
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.advance_to(30);
+    assert!(!wheel.fired_last_tick());
+    wheel.advance_to(60);
+    assert!(wheel.fired_last_tick());
+    wheel.advance_to(70);
+    assert!(!wheel.fired_last_tick());
+}
+
+#[test]
+fn test_fired_last_tick_reset_by_clear_and_drain_all() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.advance_to(60);
+    assert!(wheel.fired_last_tick());
+    wheel.clear();
+    assert!(!wheel.fired_last_tick());
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.advance_to(60);
+    assert!(wheel.fired_last_tick());
+    wheel.drain_all();
+    assert!(!wheel.fired_last_tick());
+}
+
+#[test]
+fn test_poll_remaining() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+    let (counter, new_waker) = make_waker();
+
+    let id = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    clock.advance(Duration::from_millis(120));
+    wheel.tick();
+
+    assert_eq!(
+        wheel.poll_remaining(id, &new_waker),
+        PollRemaining::Pending(Duration::from_millis(380))
+    );
+
+    // the waker was updated like a regular poll
+    clock.advance(Duration::from_millis(400));
+    wheel.tick();
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll_remaining(id, &new_waker), PollRemaining::Ready);
+}
+
+#[test]
+fn test_deadline_of() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_secs(5), &waker).unwrap();
+    let fired = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    clock.advance(Duration::from_millis(1_230));
+    wheel.tick();
+
+    assert_eq!(wheel.deadline_of(id), Some(5_000));
+    assert_eq!(wheel.deadline_of(fired), None);
+    wheel.drop(id);
+    assert_eq!(wheel.deadline_of(id), None);
+}
+
+// ============================================================================
+// state tests
+// ============================================================================
+
+#[test]
+fn test_state_through_the_lifecycle() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    assert!(wheel.contains(id));
+    assert_eq!(wheel.state(id), Some(TimerState::Pending));
+
+    clock.advance(Duration::from_millis(20));
+    wheel.tick();
+    assert!(wheel.contains(id));
+    assert_eq!(wheel.state(id), Some(TimerState::Fired));
+
+    wheel.drop(id);
+    assert!(!wheel.contains(id));
+    assert_eq!(wheel.state(id), None);
+}
+
+#[test]
+fn test_state_of_cancelled_timer() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    wheel.drop(id);
+
+    // the slot is released on cancellation
+    assert_eq!(wheel.state(id), None);
+    let reused = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    assert_eq!(wheel.state(id), None);
+    assert_eq!(wheel.state(reused), Some(TimerState::Pending));
+}
+
+// ============================================================================
+// iter tests
+// ============================================================================
+
+#[test]
+fn test_iter_empty() {
+    let wheel = TimeWheel::new();
+    assert_eq!(wheel.iter().count(), 0);
+}
+
+#[test]
+fn test_iter_yields_live_timers_with_remaining_time() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let fired = wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    let id_ms = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let cancelled = wheel.init_timer(Duration::from_millis(60), &waker).unwrap();
+    let id_s = wheel
+        .init_timer(Duration::from_millis(1500), &waker)
+        .unwrap();
+    let id_h = wheel.init_timer(Duration::from_hours(3), &waker).unwrap();
+    wheel.drop(cancelled);
+
+    wheel.advance_to(25);
+
+    let mut timers: Vec<_> = wheel.iter().collect();
+    timers.sort();
+    assert!(!timers.iter().any(|&(id, _)| id == fired || id == cancelled));
+    assert_eq!(
+        timers,
+        vec![
+            (id_ms, Duration::from_millis(30)),
+            (id_s, Duration::from_millis(1480)),
+            (id_h, Duration::from_hours(3) - Duration::from_millis(20)),
+        ]
+    );
+}
+
+#[test]
+fn test_for_each_visits_waiting_timers() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(10), &waker).unwrap();
+    let id_ms = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let id_h = wheel.init_timer(Duration::from_hours(3), &waker).unwrap();
+    wheel.advance_to(25);
+
+    let mut timers = Vec::new();
+    wheel.for_each(|id, remaining| timers.push((id, remaining)));
+    timers.sort();
+    assert_eq!(
+        timers,
+        vec![
+            (id_ms, Duration::from_millis(30)),
+            (id_h, Duration::from_hours(3) - Duration::from_millis(20)),
+        ]
+    );
+}
+
+// ============================================================================
+// rearm tests
+// ============================================================================
+
+#[test]
+fn test_rearm_reuses_the_fired_slot() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    for round in 1..=3 {
+        wheel.advance_to(round * 100);
+        assert_eq!(counter.count(), round as usize);
+        assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+
+        wheel.rearm(id, Duration::from_millis(50), &waker).unwrap();
+        assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+        assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(50)));
+    }
+    assert_eq!(wheel.storage_len(), 1);
+}
+
+#[test]
+fn test_rearm_rejects_waiting_and_released_timers() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+    assert_eq!(
+        wheel.rearm(id, Duration::from_millis(50), &waker),
+        Err(TimerError::StillPending)
+    );
+
+    wheel.advance_to(30);
+    wheel.drop(id);
+    assert_eq!(
+        wheel.rearm(id, Duration::from_millis(50), &waker),
+        Err(TimerError::UnknownTimer)
+    );
+}
+
+// ============================================================================
+// init_at tests
+// ============================================================================
+
+#[test]
+fn test_init_at_absolute_deadline() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel.advance_to(1000);
+    wheel.init_at(1200, &waker).unwrap();
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(200)));
+
+    wheel.advance_to(1200);
+    assert_eq!(counter.count(), 0);
+
+    wheel.advance_to(1210);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_init_at_past_deadline_is_ready_at_once() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel.advance_to(1000);
+    let id = wheel.init_at(500, &waker).unwrap();
+    assert_eq!(wheel.next_deadline(), None);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+
+    wheel.advance_to(1010);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn test_init_at_too_far() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let result = wheel.init_at(25 * 3_600_000, &waker);
+    assert!(matches!(
+        result,
+        Err(TimerError::DurationTooLong(err)) if err.requested >= Duration::from_hours(25)
+    ));
+}
+
+// ============================================================================
+// expire_now tests
+// ============================================================================
+
+#[test]
+fn test_expire_now_fires_waiting_timer() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    assert!(wheel.expire_now(id));
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.occupied_buckets(), (0, 0, 0, 0));
+
+    assert!(!wheel.expire_now(id));
+    wheel.advance_to(1_000);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_expire_now_rearms_interval() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_interval(Duration::from_millis(100), &waker)
+        .unwrap();
+    wheel.advance_to(50);
+    assert!(wheel.expire_now(id));
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+
+    // the next period starts from the forced fire
+    assert_eq!(wheel.advance_to_collect(150), vec![]);
+    assert_eq!(wheel.advance_to_collect(160), vec![id]);
+}
+
+// ============================================================================
+// slack tests
+// ============================================================================
+
+#[test]
+fn test_slack_joins_timer_in_window() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let ms = wheel
+        .init_timer(Duration::from_millis(100), &waker)
+        .unwrap();
+    let s = wheel
+        .init_timer(Duration::from_millis(1500), &waker)
+        .unwrap();
+    let near_ms = wheel
+        .init_timer_slack(
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+            &waker,
+        )
+        .unwrap();
+    let near_s = wheel
+        .init_timer_slack(
+            Duration::from_millis(1200),
+            Duration::from_millis(1800),
+            &waker,
+        )
+        .unwrap();
+
+    assert_eq!(wheel.advance_to_collect(110), vec![ms, near_ms]);
+    assert_eq!(wheel.advance_to_collect(1_500), vec![]);
+    assert_eq!(wheel.advance_to_collect(1_510), vec![s, near_s]);
+}
+
+#[test]
+fn test_slack_without_timer_in_window_fires_at_latest() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    wheel
+        .init_timer(Duration::from_millis(300), &waker)
+        .unwrap();
+    let id = wheel
+        .init_timer_slack(
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+            &waker,
+        )
+        .unwrap();
+
+    assert_eq!(wheel.advance_to_collect(150), vec![]);
+    assert_eq!(wheel.advance_to_collect(160), vec![id]);
+}
+
+// ============================================================================
+// callback tests
+// ============================================================================
+
+#[test]
+fn test_callback_runs_on_fire() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let ran = Arc::new(AtomicUsize::new(0));
+
+    let counter = ran.clone();
+    let id = wheel
+        .init_callback(
+            Duration::from_millis(50),
+            Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        )
+        .unwrap();
+
+    assert_eq!(wheel.advance_to(40), 0);
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+    assert_eq!(wheel.advance_to_collect(60), vec![id]);
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+    assert!(wheel.is_empty());
+}
+
+#[test]
+fn test_callback_cancelled_is_dropped_without_running() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let ran = Arc::new(AtomicUsize::new(0));
+
+    let counter = ran.clone();
+    let id = wheel
+        .init_callback(
+            Duration::from_millis(50),
+            Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        )
+        .unwrap();
+    assert_eq!(Arc::strong_count(&ran), 2);
+
+    wheel.drop(id);
+    // the closure and its captures are released right away
+    assert_eq!(Arc::strong_count(&ran), 1);
+
+    assert_eq!(wheel.advance_to(100), 0);
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_callback_poll_pending_until_fired() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_callback(Duration::from_millis(20), Box::new(|| {}))
+        .unwrap();
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+
+    wheel.advance_to(30);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    // polling does not register the waker of a callback timer
+    assert_eq!(counter.count(), 0);
+}
+
+// ============================================================================
+// broadcast tests
+// ============================================================================
+
+#[test]
+fn test_broadcast_wakes_every_subscriber() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter_a, waker_a) = make_waker();
+    let (counter_b, waker_b) = make_waker();
+
+    let id = wheel.init_broadcast(Duration::from_millis(50)).unwrap();
+    assert_eq!(wheel.subscribe(id, &waker_a), Poll::Pending);
+    assert_eq!(wheel.subscribe(id, &waker_a), Poll::Pending);
+    assert_eq!(wheel.poll(id, &waker_b), Poll::Pending);
+    assert_eq!(wheel.len(), 1);
+
+    assert_eq!(wheel.advance_to_collect(60), vec![id]);
+    assert_eq!(counter_a.count(), 1);
+    assert_eq!(counter_b.count(), 1);
+    assert_eq!(wheel.subscribe(id, &waker_a), Poll::Ready(()));
+}
+
+#[test]
+fn test_broadcast_unsubscribe() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter_a, waker_a) = make_waker();
+    let (counter_b, waker_b) = make_waker();
+
+    let id = wheel.init_broadcast(Duration::from_millis(50)).unwrap();
+    let _ = wheel.subscribe(id, &waker_a);
+    let _ = wheel.subscribe(id, &waker_b);
+    assert!(wheel.unsubscribe(id, &waker_a));
+    assert!(!wheel.unsubscribe(id, &waker_a));
+
+    wheel.advance_to(60);
+    assert_eq!(counter_a.count(), 0);
+    assert_eq!(counter_b.count(), 1);
+    assert!(!wheel.unsubscribe(id, &waker_b));
+}
+
+// ============================================================================
+// clone tests
+// ============================================================================
+
+#[test]
+fn test_clone_forks_schedule() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let short = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let long = wheel
+        .init_timer(Duration::from_millis(1500), &waker)
+        .unwrap();
+    wheel.advance_to(20);
+
+    let mut fork = wheel.clone();
+    assert_eq!(fork.drop(long), DropOutcome::Cancelled);
+    assert_eq!(wheel.len(), 2);
+
+    assert_eq!(wheel.advance_to_collect(1_510), vec![short, long]);
+    assert_eq!(fork.advance_to_collect(1_510), vec![short]);
+    assert_eq!(counter.count(), 3);
+}
+
+#[test]
+fn test_clone_keeps_callbacks_with_original() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let ran = Arc::new(AtomicUsize::new(0));
+
+    let counter = ran.clone();
+    let id = wheel
+        .init_callback(
+            Duration::from_millis(50),
+            Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        )
+        .unwrap();
+
+    let mut fork = wheel.clone();
+    assert_eq!(fork.advance_to_collect(60), vec![id]);
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+    assert_eq!(wheel.advance_to_collect(60), vec![id]);
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+}
+
+// ============================================================================
+// pause tests
+// ============================================================================
+
+#[test]
+fn test_pause_stops_ticking() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert!(!wheel.is_paused());
+
+    wheel.pause();
+    assert!(wheel.is_paused());
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(wheel.tick(), 0);
+    assert_eq!(counter.count(), 0);
+}
+
+#[test]
+fn test_resume_skips_paused_period() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    clock.advance(Duration::from_millis(30));
+    wheel.tick();
+
+    wheel.pause();
+    clock.advance(Duration::from_secs(60));
+    wheel.resume();
+    assert!(!wheel.is_paused());
+
+    // the minute spent paused is not replayed
+    assert_eq!(wheel.tick(), 0);
+    clock.advance(Duration::from_millis(20));
+    assert_eq!(wheel.tick(), 0);
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_resume_without_pause_keeps_elapsed_time() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    clock.advance(Duration::from_millis(100));
+    wheel.resume();
+
+    assert_eq!(wheel.tick(), 1);
+    assert_eq!(counter.count(), 1);
+}
+
+// ============================================================================
+// firing order tests
+// ============================================================================
+
+#[test]
+fn test_same_deadline_fires_in_arming_order() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    // every timer is due at 700ms, the first ones go through the second level and
+    // are cascaded while the last ones are armed straight into the ms level
+    let mut armed = Vec::new();
+    for ms in (210..700).step_by(10) {
+        wheel.advance_to(ms);
+        armed.push(
+            wheel
+                .init_timer(Duration::from_millis(700 - ms), &waker)
+                .unwrap(),
+        );
+    }
+    assert_eq!(armed.len(), 49);
+
+    assert_eq!(wheel.advance_to_collect(800), armed);
+}
+
+#[test]
+fn test_higher_priority_fires_first_within_a_tick() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let low = wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+    let high = wheel
+        .init_timer_with_priority(Duration::from_millis(500), 9, &waker)
+        .unwrap();
+    let mid = wheel
+        .init_timer_with_priority(Duration::from_millis(500), 3, &waker)
+        .unwrap();
+    let high_later = wheel
+        .init_timer_with_priority(Duration::from_millis(500), 9, &waker)
+        .unwrap();
+    let earlier = wheel
+        .init_timer(Duration::from_millis(490), &waker)
+        .unwrap();
+
+    assert_eq!(
+        wheel.advance_to_collect(600),
+        vec![earlier, high, high_later, mid, low]
+    );
+}
+
+#[test]
+fn test_priority_is_reset_when_a_slot_is_reused() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let released = wheel
+        .init_timer_with_priority(Duration::from_millis(50), 9, &waker)
+        .unwrap();
+    let first = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    wheel.drop(released);
+
+    let second = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert_eq!(second.index, released.index);
+    assert_eq!(wheel.advance_to_collect(60), vec![first, second]);
+}
+
+#[test]
+fn test_same_deadline_bucket_cascades_whole() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    // past the inline capacity of a bucket, all moved together down the levels
+    let mut armed: Vec<_> = (0..40)
+        .map(|_| wheel.init_timer(Duration::from_secs(30), &waker).unwrap())
+        .collect();
+    // a later deadline sharing the second bucket takes the regular path
+    armed.push(
+        wheel
+            .init_timer(Duration::from_millis(30_050), &waker)
+            .unwrap(),
+    );
+
+    assert_eq!(wheel.advance_to_collect(30_000), vec![]);
+    assert_eq!(wheel.advance_to_collect(30_010), armed[..40].to_vec());
+    assert_eq!(wheel.advance_to_collect(30_060), armed[40..].to_vec());
+    assert!(wheel.is_empty());
+}
+
+// ============================================================================
+// interval tests
+// ============================================================================
+
+#[test]
+fn test_interval_fires_every_period() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_interval(Duration::from_millis(50), &waker)
+        .unwrap();
+
+    let mut fired_at = Vec::new();
+    for ms in (0..=200).step_by(10) {
+        if wheel.advance_to(ms) > 0 {
+            fired_at.push(ms);
+        }
+    }
+    assert_eq!(fired_at, vec![60, 110, 160]);
+    assert_eq!(counter.count(), 3);
+    assert_eq!(wheel.len(), 1);
+
+    // each poll consumes one fire
+    for _ in 0..3 {
+        assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    }
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+}
+
+#[test]
+fn test_repeating_timer_stops_after_count() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_repeating(Duration::from_millis(50), 3, &waker)
+        .unwrap();
+
+    let mut fired_at = Vec::new();
+    for ms in (0..=500).step_by(10) {
+        if wheel.advance_to(ms) > 0 {
+            fired_at.push(ms);
+        }
+    }
+    assert_eq!(fired_at, vec![60, 110, 160]);
+    assert_eq!(counter.count(), 3);
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.state(id), Some(TimerState::Fired));
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_repeating_timer_zero_count_is_ready() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_repeating(Duration::from_millis(50), 0, &waker)
+        .unwrap();
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+
+    wheel.advance_to(500);
+    assert_eq!(counter.count(), 0);
+}
+
+fn jittered_fire_times(timers: usize) -> Vec<Vec<u64>> {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (_, waker) = make_waker();
+
+    let ids: Vec<_> = (0..timers)
+        .map(|_| {
+            wheel
+                .init_interval_jittered(Duration::from_secs(1), Duration::from_millis(200), &waker)
+                .unwrap()
+        })
+        .collect();
+
+    let mut fired_at = vec![Vec::new(); timers];
+    for ms in (0..=5_000).step_by(10) {
+        for id in wheel.advance_to_collect(ms) {
+            let timer = ids.iter().position(|&armed| armed == id).unwrap();
+            fired_at[timer].push(ms);
+        }
+    }
+    fired_at
+}
+
+#[test]
+fn test_jittered_interval_spreads_fires() {
+    let fired_at = jittered_fire_times(100);
+
+    for times in &fired_at {
+        assert!(times.len() >= 4, "{times:?}");
+        assert!((800..=1_210).contains(&times[0]), "{times:?}");
+        for pair in times.windows(2) {
+            assert!((800..=1_200).contains(&(pair[1] - pair[0])), "{times:?}");
+        }
+    }
+    let mut first_fires: Vec<_> = fired_at.iter().map(|times| times[0]).collect();
+    first_fires.sort();
+    first_fires.dedup();
+    assert!(first_fires.len() > 10, "{first_fires:?}");
+
+    // the offsets are deterministic
+    assert_eq!(jittered_fire_times(100), fired_at);
+}
+
+#[test]
+fn test_jittered_interval_within_the_limit() {
+    let mut wheel = TimeWheel::new();
+    let (_, waker) = make_waker();
+
+    assert_eq!(
+        wheel.init_interval_jittered(Duration::from_hours(23), Duration::from_hours(1), &waker),
+        Err(TimerError::DurationTooLong(DurationTooLong {
+            requested: Duration::from_hours(24),
+            max: Duration::from_hours(24),
+        }))
+    );
+    // a jitter larger than the period still fires at least a tick apart
+    assert!(
+        wheel
+            .init_interval_jittered(Duration::from_millis(10), Duration::from_secs(1), &waker)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_catch_up_burst_fires_every_missed_period() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_interval(Duration::from_millis(10), &waker)
+        .unwrap();
+    wheel.advance_to(1_000);
+    assert_eq!(counter.count(), 99);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+}
+
+#[test]
+fn test_catch_up_coalesce_fires_once_and_resyncs() {
+    let mut wheel =
+        TimeWheel::new_with_clock(MockClock::new()).with_catch_up(CatchUpPolicy::Coalesce);
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_interval(Duration::from_millis(10), &waker)
+        .unwrap();
+    wheel.advance_to(1_000);
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll(id, &waker), Poll::Ready(()));
+    assert_eq!(wheel.poll(id, &waker), Poll::Pending);
+
+    // re-armed as if armed at 1000ms
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(10)));
+    assert_eq!(wheel.advance_to_collect(1_010), vec![]);
+    assert_eq!(wheel.advance_to_collect(1_020), vec![id]);
+    assert_eq!(wheel.advance_to_collect(1_030), vec![id]);
+}
+
+#[test]
+fn test_interval_across_levels() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel
+        .init_interval(Duration::from_secs(30), &waker)
+        .unwrap();
+
+    wheel.advance_to(90_000);
+    assert_eq!(counter.count(), 2);
+    wheel.advance_to(90_010);
+    assert_eq!(counter.count(), 3);
+    // re-armed one period after the tick it fired on
+    assert_eq!(wheel.next_deadline(), Some(Duration::from_millis(29_990)));
+}
+
+#[test]
+fn test_interval_cancel_stops_firing() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    let id = wheel
+        .init_interval(Duration::from_millis(20), &waker)
+        .unwrap();
+    wheel.advance_to(30);
+    assert_eq!(counter.count(), 1);
+
+    wheel.drop(id);
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.advance_to(500), 0);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn test_interval_shorter_than_tick_fires_every_tick() {
+    let mut wheel = TimeWheel::new_with_clock(MockClock::new());
+    let (counter, waker) = make_waker();
+
+    wheel.init_interval(Duration::ZERO, &waker).unwrap();
+    wheel.advance_to(100);
+    // every tick but the one it was armed on
+    assert_eq!(counter.count(), 9);
+}
+
+// ============================================================================
+// run_until_idle tests
+// ============================================================================
+
+#[test]
+fn test_run_until_idle_fires_everything() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(5), &waker).unwrap();
+    wheel.init_timer(Duration::from_millis(15), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(800), &waker)
+        .unwrap();
+    wheel.init_timer(Duration::from_secs(90), &waker).unwrap();
+
+    wheel.run_until_idle();
+
+    assert_eq!(counter.count(), 4);
+    assert!(wheel.is_empty());
+    // slept just past the last deadline
+    assert_eq!(clock.now(), 90_010);
+}
+
+#[test]
+fn test_run_until_idle_with_async_sleep() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    wheel.init_timer(Duration::from_millis(30), &waker).unwrap();
+    wheel
+        .init_timer(Duration::from_millis(500), &waker)
+        .unwrap();
+
+    let mut run = pin!(wheel.run_until_idle_with(|duration| {
+        clock.advance(duration);
+        std::future::ready(())
+    }));
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(run.as_mut().poll(&mut cx), Poll::Ready(()));
+
+    assert_eq!(counter.count(), 2);
+    assert_eq!(clock.now(), 510);
+}
+
+// ============================================================================
+// poll_fired_at tests
+// ============================================================================
+
+#[test]
+fn test_poll_fired_at_reports_firing_tick() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    assert_eq!(wheel.poll_fired_at(id, &waker), Poll::Pending);
+
+    // the advance runs late, the timer still reports the tick that fired it
+    clock.advance(Duration::from_millis(100));
+    wheel.advance_to(100);
+    assert_eq!(wheel.poll_fired_at(id, &waker), Poll::Ready(60));
+}
+
+#[test]
+fn test_poll_fired_at_on_a_lagging_wheel() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (counter, waker) = make_waker();
+
+    // the wheel is still at 0 when the timer is armed, its deadline is 115ms
+    clock.advance(Duration::from_millis(95));
+    let id = wheel.init_timer(Duration::from_millis(20), &waker).unwrap();
+
+    clock.advance(Duration::from_millis(35));
+    wheel.tick();
+    assert_eq!(counter.count(), 1);
+    assert_eq!(wheel.poll_fired_at(id, &waker), Poll::Ready(115));
+}
+
+#[test]
+fn test_poll_fired_at_due_by_poll() {
+    let clock = MockClock::new();
+    let mut wheel = TimeWheel::new_with_clock(clock.clone());
+    let (_counter, waker) = make_waker();
+
+    let id = wheel.init_timer(Duration::from_millis(50), &waker).unwrap();
+    let done = wheel.init_timer(Duration::ZERO, &waker).unwrap();
+    assert_eq!(wheel.poll_fired_at(done, &waker), Poll::Ready(0));
+
+    clock.advance(Duration::from_millis(75));
+    assert_eq!(wheel.poll_fired_at(id, &waker), Poll::Ready(75));
 }